@@ -0,0 +1,410 @@
+use algorithm_study::sorting::{sorters, Complexity, Sorter};
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "\
+Usage: sort-bench [OPTIONS]
+
+Runs every sorting algorithm over inputs of several sizes and distributions,
+and prints the wall time and throughput of each run.
+
+Options:
+  --sizes N,N,...        input sizes [default: 1000,10000,100000,1000000]
+  --dist NAME,NAME,...   input distributions [default: all]
+                         random, sorted, reversed, few-unique, nearly-sorted
+  --algo NAME,NAME,...   algorithms to run, e.g. quick_sort [default: all]
+  --repeat N             runs per input, the fastest one is reported [default: 3]
+  --budget SECONDS       time budget per run; an algorithm is skipped on inputs
+                         where it is expected to exceed it, estimated from the
+                         previous size or a 1000-element trial run [default: 1]
+  --range N              random values are drawn from 0..N [default: 1000000]
+  --seed N               seed of the random inputs [default: 42]
+  --csv PATH             also write the results as CSV to PATH
+  -h, --help             print this help
+";
+
+/// 输入的分布
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Distribution {
+    Random,
+    Sorted,
+    Reversed,
+    FewUnique,
+    NearlySorted,
+}
+
+impl Distribution {
+    const ALL: [Distribution; 5] = [
+        Distribution::Random,
+        Distribution::Sorted,
+        Distribution::Reversed,
+        Distribution::FewUnique,
+        Distribution::NearlySorted,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Distribution::Random => "random",
+            Distribution::Sorted => "sorted",
+            Distribution::Reversed => "reversed",
+            Distribution::FewUnique => "few-unique",
+            Distribution::NearlySorted => "nearly-sorted",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Distribution> {
+        Distribution::ALL
+            .into_iter()
+            .find(|dist| dist.name() == name)
+    }
+
+    /// 生成长度为 `len` 的输入，取值都在 `0..range` 中
+    fn generate(self, len: usize, range: u64, seed: &mut u64) -> Vec<u64> {
+        // 有序的输入均匀地分布在 0..range 中
+        let sorted = || (0..len as u64).map(move |i| i * range / len as u64);
+
+        match self {
+            Distribution::Random => (0..len).map(|_| random(seed) % range).collect(),
+            Distribution::Sorted => sorted().collect(),
+            Distribution::Reversed => sorted().rev().collect(),
+            Distribution::FewUnique => (0..len).map(|_| random(seed) % 8).collect(),
+            Distribution::NearlySorted => {
+                // 随机交换 1% 的元素
+                let mut vec: Vec<u64> = sorted().collect();
+                for _ in 0..len / 100 {
+                    let i = random(seed) as usize % len;
+                    let j = random(seed) as usize % len;
+                    vec.swap(i, j);
+                }
+                vec
+            }
+        }
+    }
+}
+
+/// 命令行参数
+struct Options {
+    sizes: Vec<usize>,
+    dists: Vec<Distribution>,
+    algos: Option<Vec<String>>,
+    repeat: usize,
+    budget: Duration,
+    range: u64,
+    seed: u64,
+    csv: Option<String>,
+}
+
+impl Options {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        let mut options = Options {
+            sizes: vec![1_000, 10_000, 100_000, 1_000_000],
+            dists: Distribution::ALL.to_vec(),
+            algos: None,
+            repeat: 3,
+            budget: Duration::from_secs(1),
+            range: 1_000_000,
+            seed: 42,
+            csv: None,
+        };
+
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                print!("{}", USAGE);
+                process::exit(0);
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {}", arg))?;
+            match arg.as_str() {
+                "--sizes" => options.sizes = parse_list(&value, parse_number)?,
+                "--dist" => {
+                    options.dists = parse_list(&value, |s| {
+                        Distribution::from_name(s)
+                            .ok_or_else(|| format!("unknown distribution: {}", s))
+                    })?
+                }
+                "--algo" => options.algos = Some(parse_list(&value, |s| Ok(s.to_string()))?),
+                "--repeat" => options.repeat = parse_number::<usize>(&value)?.max(1),
+                "--budget" => {
+                    let secs: f64 = value
+                        .parse()
+                        .map_err(|_| format!("invalid budget: {}", value))?;
+                    options.budget = Duration::try_from_secs_f64(secs)
+                        .map_err(|_| format!("invalid budget: {}", value))?;
+                }
+                "--range" => options.range = parse_number::<u64>(&value)?.max(1),
+                "--seed" => options.seed = parse_number(&value)?,
+                "--csv" => options.csv = Some(value),
+                _ => return Err(format!("unknown option: {}", arg)),
+            }
+        }
+
+        // 从小到大运行，才能根据较小输入的耗时预估是否会超时
+        options.sizes.sort_unstable();
+        options.sizes.dedup();
+        Ok(options)
+    }
+}
+
+fn parse_list<T, F>(value: &str, parse: F) -> Result<Vec<T>, String>
+where
+    F: FnMut(&str) -> Result<T, String>,
+{
+    value.split(',').map(str::trim).map(parse).collect()
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .replace('_', "")
+        .parse()
+        .map_err(|_| format!("invalid number: {}", value))
+}
+
+/// 简单的线性同余伪随机数
+fn random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 16
+}
+
+/// 一次测量的结果
+enum Outcome {
+    /// 多次运行中最快的一次
+    Done(Duration),
+    /// 预计会超过时间预算，没有运行
+    Skipped,
+}
+
+struct Row {
+    algo: &'static str,
+    dist: Distribution,
+    size: usize,
+    outcome: Outcome,
+}
+
+impl Row {
+    fn seconds(&self) -> Option<f64> {
+        match self.outcome {
+            Outcome::Done(time) => Some(time.as_secs_f64()),
+            Outcome::Skipped => None,
+        }
+    }
+
+    /// 每秒排序的元素个数
+    fn throughput(&self) -> Option<f64> {
+        self.seconds()
+            .filter(|&secs| secs > 0.0)
+            .map(|secs| self.size as f64 / secs)
+    }
+}
+
+/// 按复杂度估计输入从 `from` 个元素增长到 `to` 个时，耗时增长的倍数
+fn growth(complexity: Complexity, from: usize, to: usize) -> f64 {
+    let (from, to) = (from.max(2) as f64, to.max(2) as f64);
+    match complexity {
+        Complexity::Linear => to / from,
+        Complexity::Linearithmic => to * to.log2() / (from * from.log2()),
+        Complexity::LogSquared => to * to.log2().powi(2) / (from * from.log2().powi(2)),
+        Complexity::Quadratic => (to / from).powi(2),
+    }
+}
+
+/// 第一个输入大小超过该值时，先在这么多个元素上试运行，用来预估第一个输入的耗时
+const CALIBRATION_SIZE: usize = 1000;
+
+/// 对一个算法和一种分布，按从小到大的顺序测量所有输入大小
+fn bench(sorter: &dyn Sorter<u64>, dist: Distribution, options: &Options) -> Vec<Row> {
+    let mut rows = Vec::new();
+
+    // 第一个输入也要预估耗时，否则只给一个很大的输入时，平方级的算法仍会运行很久
+    let mut last: Option<(usize, Duration)> = match options.sizes.first() {
+        Some(&size) if size > CALIBRATION_SIZE => Some((
+            CALIBRATION_SIZE,
+            measure(sorter, dist, CALIBRATION_SIZE, options),
+        )),
+        _ => None,
+    };
+
+    for &size in &options.sizes {
+        // 根据上一次的耗时预估这一次的耗时，超出预算就跳过
+        let expected =
+            last.map(|(n, time)| time.as_secs_f64() * growth(sorter.worst_case(), n, size));
+        if expected.is_some_and(|secs| secs > options.budget.as_secs_f64()) {
+            rows.push(Row {
+                algo: sorter.name(),
+                dist,
+                size,
+                outcome: Outcome::Skipped,
+            });
+            continue;
+        }
+
+        let best = measure(sorter, dist, size, options);
+        last = Some((size, best));
+        rows.push(Row {
+            algo: sorter.name(),
+            dist,
+            size,
+            outcome: Outcome::Done(best),
+        });
+    }
+
+    rows
+}
+
+/// 在长度为 `size` 的输入上运行 `sorter`，返回多次运行中最快的一次
+fn measure(
+    sorter: &dyn Sorter<u64>,
+    dist: Distribution,
+    size: usize,
+    options: &Options,
+) -> Duration {
+    // 每个算法在同样的输入上运行
+    let mut seed = options.seed;
+    let input = dist.generate(size, options.range, &mut seed);
+
+    let mut best = Duration::MAX;
+    for _ in 0..options.repeat {
+        let mut vec = input.clone();
+        let start = Instant::now();
+        sorter.sort(&mut vec);
+        let time = start.elapsed();
+
+        assert!(
+            vec.windows(2).all(|w| w[0] <= w[1]),
+            "{} returned an unsorted result on {} input of size {}",
+            sorter.name(),
+            dist.name(),
+            size
+        );
+
+        best = best.min(time);
+        if time > options.budget {
+            break;
+        }
+    }
+    best
+}
+
+/// 表格的一行，运行时边测量边输出，算法一列宽 `width`
+fn print_row<W: Write>(out: &mut W, row: &Row, width: usize) -> io::Result<()> {
+    let (time, throughput) = match (row.seconds(), row.throughput()) {
+        (Some(secs), Some(rate)) => (format!("{:.3}", secs * 1e3), format!("{:.2}", rate / 1e6)),
+        (Some(secs), None) => (format!("{:.3}", secs * 1e3), "-".to_string()),
+        (None, _) => ("skipped".to_string(), "-".to_string()),
+    };
+    writeln!(
+        out,
+        "{:<width$} {:<14} {:>10} {:>12} {:>10}",
+        row.algo,
+        row.dist.name(),
+        row.size,
+        time,
+        throughput
+    )?;
+    out.flush()
+}
+
+fn write_csv<W: Write>(out: &mut W, rows: &[Row]) -> io::Result<()> {
+    writeln!(
+        out,
+        "algorithm,distribution,size,seconds,elements_per_second,status"
+    )?;
+    for row in rows {
+        let status = match row.outcome {
+            Outcome::Done(_) => "ok",
+            Outcome::Skipped => "skipped",
+        };
+        let seconds = row.seconds().map(|s| s.to_string()).unwrap_or_default();
+        let throughput = row.throughput().map(|r| r.to_string()).unwrap_or_default();
+        writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            row.algo,
+            row.dist.name(),
+            row.size,
+            seconds,
+            throughput,
+            status
+        )?;
+    }
+    Ok(())
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let all = sorters::<u64>();
+    let selected: Vec<&dyn Sorter<u64>> = match &options.algos {
+        None => all.iter().map(|sorter| sorter.as_ref()).collect(),
+        Some(names) => names
+            .iter()
+            .map(|name| {
+                all.iter()
+                    .find(|sorter| sorter.name() == name)
+                    .map(|sorter| sorter.as_ref())
+                    .ok_or_else(|| format!("unknown algorithm: {}", name))
+            })
+            .collect::<Result<_, _>>()?,
+    };
+
+    // 先创建 CSV 文件，路径有误时不必等所有测量结束才报错
+    let csv = match &options.csv {
+        Some(path) => {
+            let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+            Some((path, BufWriter::new(file)))
+        }
+        None => None,
+    };
+
+    // 算法一列按最长的名称对齐
+    let width = selected
+        .iter()
+        .map(|sorter| sorter.name().len())
+        .fold("algorithm".len(), usize::max);
+
+    let mut stdout = io::stdout().lock();
+    writeln!(
+        stdout,
+        "{:<width$} {:<14} {:>10} {:>12} {:>10}",
+        "algorithm", "distribution", "size", "time (ms)", "Melem/s"
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut rows = Vec::new();
+    for sorter in selected {
+        for &dist in &options.dists {
+            for row in bench(sorter, dist, options) {
+                print_row(&mut stdout, &row, width).map_err(|e| e.to_string())?;
+                rows.push(row);
+            }
+        }
+    }
+
+    if let Some((path, mut writer)) = csv {
+        write_csv(&mut writer, &rows)
+            .and_then(|()| writer.flush())
+            .map_err(|e| format!("{}: {}", path, e))?;
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("sort-bench: {}", message);
+            eprintln!("Try 'sort-bench --help' for more information.");
+            process::exit(2);
+        }
+    };
+
+    if let Err(message) = run(&options) {
+        eprintln!("sort-bench: {}", message);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,397 @@
+use algorithm_study::sorting::{comparison_sorters, Sorter};
+use std::cmp::Ordering;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process;
+
+const USAGE: &str = "\
+Usage: sort-cli [OPTIONS] [FILE]...
+
+Sorts the lines of all FILEs (or standard input) and writes them to standard
+output. With no FILE, or when FILE is -, reads standard input.
+
+Options:
+  -a, --algo NAME        sorting algorithm [default: merge]
+                         short names such as quick, merge, heap, tim, or the
+                         full function names such as quick_sort
+  -n, --numeric          compare the keys as numbers; keys that are not
+                         numbers sort before all numbers
+  -r, --reverse          reverse the result of comparisons
+  -u, --unique           output only the first of lines with equal keys
+  -s, --stable           keep lines with equal keys in input order instead of
+                         comparing the whole lines as a last resort
+  -k, --key N            sort by the N-th field (starting at 1) of each line
+  -t, --delimiter CHAR   fields are separated by CHAR instead of blanks
+  -o, --output FILE      write the result to FILE instead of standard output
+      --list             list the available algorithms
+  -h, --help             print this help
+";
+
+/// 命令行参数
+struct Options {
+    algo: String,
+    numeric: bool,
+    reverse: bool,
+    unique: bool,
+    stable: bool,
+    key: Option<usize>,
+    delimiter: Option<u8>,
+    output: Option<String>,
+    files: Vec<String>,
+}
+
+impl Options {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        let mut options = Options {
+            algo: "merge".to_string(),
+            numeric: false,
+            reverse: false,
+            unique: false,
+            stable: false,
+            key: None,
+            delimiter: None,
+            output: None,
+            files: Vec::new(),
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("missing value for {}", name))
+            };
+
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    print!("{}", USAGE);
+                    process::exit(0);
+                }
+                "--list" => {
+                    for sorter in comparison_sorters::<Record>() {
+                        println!("{:<16} {}", short_name(sorter.name()), sorter.name());
+                    }
+                    process::exit(0);
+                }
+                "-a" | "--algo" => options.algo = value(&arg)?,
+                "-n" | "--numeric" => options.numeric = true,
+                "-r" | "--reverse" => options.reverse = true,
+                "-u" | "--unique" => options.unique = true,
+                "-s" | "--stable" => options.stable = true,
+                "-k" | "--key" => {
+                    let value = value(&arg)?;
+                    match value.parse() {
+                        Ok(n) if n > 0 => options.key = Some(n),
+                        _ => return Err(format!("invalid field number: {}", value)),
+                    }
+                }
+                "-t" | "--delimiter" => {
+                    let value = value(&arg)?;
+                    match value.as_bytes() {
+                        &[delimiter] => options.delimiter = Some(delimiter),
+                        _ => return Err(format!("delimiter must be a single byte: {}", value)),
+                    }
+                }
+                "-o" | "--output" => options.output = Some(value(&arg)?),
+                "-" => options.files.push(arg),
+                _ if arg.starts_with('-') => return Err(format!("unknown option: {}", arg)),
+                _ => options.files.push(arg),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// 算法的简称：去掉名称中的 `_sort`，如 `quick_sort_3way` 的简称是 `quick_3way`
+fn short_name(name: &str) -> String {
+    name.replace("_sort", "")
+}
+
+/// 按全名或简称查找算法，`-` 与 `_` 视为相同
+fn find_sorter(name: &str) -> Result<Box<dyn Sorter<Record>>, String> {
+    let name = name.replace('-', "_");
+    comparison_sorters()
+        .into_iter()
+        .find(|sorter| sorter.name() == name || short_name(sorter.name()) == name)
+        .ok_or_else(|| {
+            format!(
+                "unknown algorithm: {} (only comparison sorts are supported, see --list)",
+                name
+            )
+        })
+}
+
+/// 行的排序键
+#[derive(Debug, Clone, PartialEq)]
+enum Key {
+    /// 不是数字的键排在所有数字之前
+    Number(Option<f64>),
+    Bytes(Vec<u8>),
+}
+
+impl Key {
+    fn cmp(&self, other: &Key) -> Ordering {
+        match (self, other) {
+            (Key::Number(a), Key::Number(b)) => match (a, b) {
+                (Some(a), Some(b)) => a.total_cmp(b),
+                _ => a.is_some().cmp(&b.is_some()),
+            },
+            (Key::Bytes(a), Key::Bytes(b)) => a.cmp(b),
+            // 同一次排序中所有的键类型相同
+            _ => unreachable!("keys of different kinds"),
+        }
+    }
+}
+
+/// 被排序的一行。
+///
+/// 比较时先比较键，再视参数比较整行，最后比较行号，因此任意两行都不相等，
+/// 不稳定的算法也能得到唯一确定的结果。
+#[derive(Debug)]
+struct Record {
+    key: Key,
+    line: Vec<u8>,
+    index: usize,
+    reverse: bool,
+    /// 键相等时是否比较整行
+    last_resort: bool,
+}
+
+impl Record {
+    fn cmp_key(&self, other: &Record) -> Ordering {
+        let ord = self.key.cmp(&other.key);
+        if self.reverse {
+            ord.reverse()
+        } else {
+            ord
+        }
+    }
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Record {}
+
+impl PartialOrd for Record {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Record {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_key(other)
+            .then_with(|| {
+                if !self.last_resort {
+                    Ordering::Equal
+                } else if self.reverse {
+                    other.line.cmp(&self.line)
+                } else {
+                    self.line.cmp(&other.line)
+                }
+            })
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// 取出一行中作为键的部分
+fn key_bytes<'a>(line: &'a [u8], options: &Options) -> &'a [u8] {
+    let n = match options.key {
+        Some(n) => n,
+        None => return line,
+    };
+
+    let field = match options.delimiter {
+        Some(delimiter) => line.split(|&b| b == delimiter).nth(n - 1),
+        None => line
+            .split(|b| b.is_ascii_whitespace())
+            .filter(|field| !field.is_empty())
+            .nth(n - 1),
+    };
+    field.unwrap_or(&[])
+}
+
+fn make_key(bytes: &[u8], options: &Options) -> Key {
+    if options.numeric {
+        let number = std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .filter(|x| !x.is_nan());
+        Key::Number(number)
+    } else {
+        Key::Bytes(bytes.to_vec())
+    }
+}
+
+/// 按参数排序所有行
+fn sort_lines(lines: Vec<Vec<u8>>, sorter: &dyn Sorter<Record>, options: &Options) -> Vec<Vec<u8>> {
+    let mut records: Vec<Record> = lines
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| Record {
+            key: make_key(key_bytes(&line, options), options),
+            line,
+            index,
+            reverse: options.reverse,
+            last_resort: !options.stable && !options.unique,
+        })
+        .collect();
+
+    sorter.sort(&mut records);
+
+    if options.unique {
+        records.dedup_by(|b, a| a.cmp_key(b) == Ordering::Equal);
+    }
+    records.into_iter().map(|record| record.line).collect()
+}
+
+/// 读入一个输入的所有行，去掉行尾的换行符
+fn read_lines<R: BufRead>(mut input: R, lines: &mut Vec<Vec<u8>>) -> io::Result<()> {
+    loop {
+        let mut line = Vec::new();
+        if input.read_until(b'\n', &mut line)? == 0 {
+            return Ok(());
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        lines.push(line);
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let sorter = find_sorter(&options.algo)?;
+
+    let mut lines = Vec::new();
+    if options.files.is_empty() {
+        read_lines(io::stdin().lock(), &mut lines).map_err(|e| format!("-: {}", e))?;
+    }
+    for path in &options.files {
+        let result = if path == "-" {
+            read_lines(io::stdin().lock(), &mut lines)
+        } else {
+            File::open(path).and_then(|file| read_lines(BufReader::new(file), &mut lines))
+        };
+        result.map_err(|e| format!("{}: {}", path, e))?;
+    }
+
+    let lines = sort_lines(lines, sorter.as_ref(), options);
+
+    // 读完所有输入后再创建输出文件，输出文件也可以是输入之一
+    let (name, output): (&str, Box<dyn Write>) = match &options.output {
+        Some(path) => (
+            path,
+            Box::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?),
+        ),
+        None => ("-", Box::new(io::stdout().lock())),
+    };
+    let mut output = BufWriter::new(output);
+    lines
+        .iter()
+        .try_for_each(|line| {
+            output.write_all(line)?;
+            output.write_all(b"\n")
+        })
+        .and_then(|()| output.flush())
+        .map_err(|e| format!("{}: {}", name, e))
+}
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("sort-cli: {}", message);
+            eprintln!("Try 'sort-cli --help' for more information.");
+            process::exit(2);
+        }
+    };
+
+    if let Err(message) = run(&options) {
+        eprintln!("sort-cli: {}", message);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// 用给定的参数排序 `input` 的各行
+    fn sort(args: &[&str], input: &str) -> String {
+        let options = Options::parse(args.iter().map(|s| s.to_string())).unwrap();
+        let sorter = find_sorter(&options.algo).unwrap();
+        let lines = input.lines().map(|s| s.as_bytes().to_vec()).collect();
+
+        let lines = sort_lines(lines, sorter.as_ref(), &options);
+        lines
+            .into_iter()
+            .map(|line| String::from_utf8(line).unwrap() + "\n")
+            .collect()
+    }
+
+    #[test]
+    fn test_modes() {
+        let input = "10\n9\nb\n-1.5\na\n9\n";
+
+        assert_eq!(sort(&[], input), "-1.5\n10\n9\n9\na\nb\n");
+        assert_eq!(sort(&["-n"], input), "a\nb\n-1.5\n9\n9\n10\n");
+        assert_eq!(sort(&["-n", "-r"], input), "10\n9\n9\n-1.5\nb\na\n");
+        assert_eq!(sort(&["-n", "-u"], input), "b\n-1.5\n9\n10\n");
+        assert_eq!(sort(&["-u"], input), "-1.5\n10\n9\na\nb\n");
+    }
+
+    #[test]
+    fn test_key() {
+        let input = "x 3 c\ny  1 a\nz 2\nw 1 b\n";
+
+        assert_eq!(sort(&["-k", "2"], input), "w 1 b\ny  1 a\nz 2\nx 3 c\n");
+        assert_eq!(
+            sort(&["-k", "2", "-s"], input),
+            "y  1 a\nw 1 b\nz 2\nx 3 c\n"
+        );
+        assert_eq!(sort(&["-k", "3"], input), "z 2\ny  1 a\nw 1 b\nx 3 c\n");
+
+        let input = "b,2\na,10\nc,2\n";
+        assert_eq!(
+            sort(&["-t", ",", "-k", "2", "-n"], input),
+            "b,2\nc,2\na,10\n"
+        );
+        assert_eq!(
+            sort(&["-t", ",", "-k", "2", "-n", "-r", "-s"], input),
+            "a,10\nb,2\nc,2\n"
+        );
+    }
+
+    #[test]
+    fn test_algorithms() {
+        let input: String = (0..200)
+            .map(|i| format!("{} {}\n", (i * 37) % 11, i))
+            .collect();
+        let expected = sort(&["-k", "1", "-n", "-s"], &input);
+
+        // 所有算法的结果都相同，不稳定的算法也保持输入顺序
+        for sorter in comparison_sorters::<Record>() {
+            let args = ["-a", sorter.name(), "-k", "1", "-n", "-s"];
+            assert_eq!(sort(&args, &input), expected, "{}", sorter.name());
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        assert!(find_sorter("quick").is_ok());
+        assert!(find_sorter("par-merge").is_ok());
+        assert!(find_sorter("quick_sort_3way").is_ok());
+        assert!(find_sorter("radix_lsd").is_err());
+
+        let parse = |args: &[&str]| Options::parse(args.iter().map(|s| s.to_string()));
+        assert!(parse(&["-k", "0"]).is_err());
+        assert!(parse(&["-t", "::"]).is_err());
+        assert!(parse(&["--algo"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+        assert_eq!(parse(&["a", "-", "b"]).unwrap().files, vec!["a", "-", "b"]);
+    }
+}
@@ -1,5 +1,7 @@
 pub mod sorting;
 
+pub mod search;
+
 pub mod fibonacci;
 
 pub mod list;
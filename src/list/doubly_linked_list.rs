@@ -1,5 +1,6 @@
 use super::List;
 use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::fmt::Display;
 use std::rc::{Rc, Weak};
 
@@ -38,6 +39,99 @@ impl<T> DoublyLinkedList<T> {
             ptr
         }
     }
+
+    /// Stable, O(n log n) merge sort that relinks the nodes instead of moving values.
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        // Sort through the `next` links only, the same way as the singly linked list,
+        // then rebuild `prev` and `tail` in one pass
+        let mut bins: Vec<Option<Next<T>>> = Vec::new();
+        let mut rest = self.head.take();
+        self.tail = None;
+
+        while let Some(node) = rest {
+            rest = node.borrow_mut().next.take();
+
+            let mut run = Some(node);
+            let mut i = 0;
+            while let Some(bin) = bins.get_mut(i).and_then(Option::take) {
+                run = merge(Some(bin), run, &mut compare);
+                i += 1;
+            }
+            match bins.get_mut(i) {
+                Some(bin) => *bin = run,
+                None => bins.push(run),
+            }
+        }
+
+        self.head = bins
+            .into_iter()
+            .fold(None, |run, bin| merge(bin, run, &mut compare));
+
+        let mut prev: Option<Prev<T>> = None;
+        let mut ptr = self.head.clone();
+        while let Some(node) = ptr {
+            node.borrow_mut().prev = prev;
+            prev = Some(Rc::downgrade(&node));
+            ptr = node.borrow().next.clone();
+            if ptr.is_none() {
+                self.tail = Some(node);
+            }
+        }
+    }
+
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+}
+
+/// Merge two sorted lists through their `next` links, taking from `left` on ties
+fn merge<T, F>(
+    mut left: Option<Next<T>>,
+    mut right: Option<Next<T>>,
+    compare: &mut F,
+) -> Option<Next<T>>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut head: Option<Next<T>> = None;
+    let mut tail: Option<Next<T>> = None;
+
+    while let (Some(l), Some(r)) = (&left, &right) {
+        let from = if compare(&r.borrow().value, &l.borrow().value) == Ordering::Less {
+            &mut right
+        } else {
+            &mut left
+        };
+
+        let node = from.take().unwrap();
+        *from = node.borrow_mut().next.take();
+        match &tail {
+            Some(tail) => tail.borrow_mut().next = Some(node.clone()),
+            None => head = Some(node.clone()),
+        }
+        tail = Some(node);
+    }
+
+    let rest = left.or(right);
+    match tail {
+        Some(tail) => tail.borrow_mut().next = rest,
+        None => head = rest,
+    }
+    head
 }
 
 impl<T> List<T> for DoublyLinkedList<T> {
@@ -312,6 +406,44 @@ mod test {
         assert_eq!(None, val);
     }
 
+    #[test]
+    fn test_sort() {
+        let mut list = DoublyLinkedList::<i32>::new();
+        list.sort();
+        assert_eq!("**[Empty]**", list.to_string());
+
+        let mut list = DoublyLinkedList::from_slice([3, 5, 2, 4, 1]);
+        list.sort();
+        assert_eq!("[1, 2, 3, 4, 5]", list.to_string());
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!("[5, 4, 3, 2, 1]", list.to_string());
+
+        for len in [1, 2, 3, 7, 8, 9, 100, 1000] {
+            // (key, original position)
+            let vec: Vec<(u32, usize)> = (0..len).map(|i| (i as u32 * 7919 % 13, i)).collect();
+            let mut expected = vec.clone();
+            expected.sort_by_key(|x| x.0);
+
+            let mut list = DoublyLinkedList::from_slice(vec);
+            list.sort_by_key(|x| x.0);
+            assert_eq!(len, list.len());
+
+            // Both ends are usable after sorting
+            list.push_back((99, len));
+            list.push_front((0, len + 1));
+            expected.push((99, len));
+            expected.insert(0, (0, len + 1));
+
+            // Walk backwards through the `prev` links, equal keys keep their original order
+            let mut sorted = Vec::new();
+            while let Some(x) = list.pop_back() {
+                sorted.push(x);
+            }
+            sorted.reverse();
+            assert_eq!(expected, sorted);
+        }
+    }
+
     #[test]
     fn test_iter() {
         let list = DoublyLinkedList::from_slice(&[1, 2, 3, 4, 5]);
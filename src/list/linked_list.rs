@@ -1,4 +1,5 @@
 use super::List;
+use std::cmp::Ordering;
 use std::fmt::Display;
 
 // #[derive(Clone)]
@@ -60,6 +61,80 @@ impl<T> LinkedList<T> {
         next_pointer.next = Some(prev_pointer);
         self.head = Some(next_pointer);
     }
+
+    /// Stable, O(n log n) merge sort that relinks the nodes instead of moving values.
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        // Bottom-up: bins[i] is either empty or a sorted run of 2^i nodes,
+        // and higher bins always hold nodes that came earlier in the list
+        let mut bins: Vec<Option<Box<Node<T>>>> = Vec::new();
+        let mut rest = self.head.take();
+
+        while let Some(mut node) = rest {
+            rest = node.next.take();
+
+            let mut run = Some(node);
+            let mut i = 0;
+            while let Some(bin) = bins.get_mut(i).and_then(Option::take) {
+                run = merge(Some(bin), run, &mut compare);
+                i += 1;
+            }
+            match bins.get_mut(i) {
+                Some(bin) => *bin = run,
+                None => bins.push(run),
+            }
+        }
+
+        // Lower bins hold later nodes, so they go on the right
+        self.head = bins
+            .into_iter()
+            .fold(None, |run, bin| merge(bin, run, &mut compare));
+    }
+
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+}
+
+/// Merge two sorted lists, taking from `left` on ties to keep the sort stable
+fn merge<T, F>(
+    mut left: Option<Box<Node<T>>>,
+    mut right: Option<Box<Node<T>>>,
+    compare: &mut F,
+) -> Option<Box<Node<T>>>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut head = None;
+    let mut tail = &mut head;
+
+    while let (Some(l), Some(r)) = (&left, &right) {
+        let from = if compare(&r.value, &l.value) == Ordering::Less {
+            &mut right
+        } else {
+            &mut left
+        };
+
+        let mut node = from.take().unwrap();
+        *from = node.next.take();
+        tail = &mut tail.insert(node).next;
+    }
+
+    *tail = left.or(right);
+    head
 }
 
 impl<T> List<T> for LinkedList<T> {
@@ -323,6 +398,35 @@ mod test {
         assert_eq!(None, val);
     }
 
+    #[test]
+    fn test_sort() {
+        let mut list = LinkedList::<i32>::new();
+        list.sort();
+        assert_eq!("**[Empty]**", list.to_string());
+
+        let mut list = LinkedList::from_slice([3, 5, 2, 4, 1]);
+        list.sort();
+        assert_eq!("[1, 2, 3, 4, 5]", list.to_string());
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!("[5, 4, 3, 2, 1]", list.to_string());
+
+        for len in [1, 2, 3, 7, 8, 9, 100, 1000] {
+            // (key, original position)
+            let vec: Vec<(u32, usize)> = (0..len).map(|i| (i as u32 * 7919 % 13, i)).collect();
+            let mut expected = vec.clone();
+            expected.sort_by_key(|x| x.0);
+
+            let mut list = LinkedList::from_slice(vec);
+            list.sort_by_key(|x| x.0);
+            assert_eq!(len, list.len());
+
+            // Equal keys keep their original order
+            list.push_back((99, len));
+            expected.push((99, len));
+            assert_eq!(expected, list.into_iter().collect::<Vec<_>>());
+        }
+    }
+
     #[test]
     fn test_iter() {
         let list = LinkedList::from_slice(&[1, 2, 3, 4, 5]);
@@ -0,0 +1,102 @@
+use super::{found, partition_point};
+use std::cmp::Ordering;
+
+/// # 指数查找
+///
+/// 又叫倍增查找、galloping 查找。依次检查第 1、2、4、8…… 个元素，
+/// 直到遇到不小于 `x` 的元素，再在最后一次倍增跨过的范围内二分查找。
+///
+/// 时间复杂度 O(log i)，`i` 是结果的位置，目标靠近开头时比二分查找快，
+/// 也适用于不知道长度的序列。返回值与 [`binary_search`](super::binary_search) 相同。
+///
+/// ```
+/// # use algorithm_study::search::exponential_search;
+/// #
+/// let vec: Vec<u32> = (0..1_000_000).map(|x| x / 2).collect();
+///
+/// assert_eq!(exponential_search(&vec, &3), Ok(6));
+/// assert_eq!(exponential_search(&vec, &499_999), Ok(999_998));
+/// assert_eq!(exponential_search(&vec, &500_000), Err(1_000_000));
+/// ```
+///
+pub fn exponential_search<T>(vec: &[T], x: &T) -> Result<usize, usize>
+where
+    T: Ord,
+{
+    exponential_search_by(vec, |e| e.cmp(x))
+}
+
+/// # 指数查找（自定义比较）
+///
+/// 与 [`exponential_search`] 相同，但用 `f` 比较元素与目标，
+/// 含义与 [`lower_bound_by`](super::lower_bound_by) 相同。
+///
+/// ```
+/// # use algorithm_study::search::exponential_search_by;
+/// #
+/// let vec = vec![9, 7, 7, 4, 1];
+///
+/// assert_eq!(exponential_search_by(&vec, |e| 7.cmp(e)), Ok(1));
+/// assert_eq!(exponential_search_by(&vec, |e| 5.cmp(e)), Err(3));
+/// ```
+///
+pub fn exponential_search_by<T, F>(vec: &[T], mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let len = vec.len();
+
+    // lo 之前的元素都小于目标；hi <= len 时 vec[hi - 1] 不小于目标
+    let (mut lo, mut hi) = (0, 1);
+    while hi <= len && f(&vec[hi - 1]) == Ordering::Less {
+        lo = hi;
+        hi = hi.saturating_mul(2);
+    }
+    let hi = hi.min(len);
+
+    let i = lo + partition_point(&vec[lo..hi], |e| f(e) == Ordering::Less);
+    found(vec, i, f)
+}
+
+/// # 指数查找（按键查找）
+///
+/// 与 [`exponential_search`] 相同，但比较 `f` 提取的键与 `key`。
+///
+/// ```
+/// # use algorithm_study::search::exponential_search_by_key;
+/// #
+/// let vec = vec![("a", 1), ("b", 3), ("c", 3), ("d", 5)];
+///
+/// assert_eq!(exponential_search_by_key(&vec, &3, |x| x.1), Ok(1));
+/// ```
+///
+pub fn exponential_search_by_key<T, K, F>(vec: &[T], key: &K, mut f: F) -> Result<usize, usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    exponential_search_by(vec, |e| f(e).cmp(key))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_near_front() {
+        // 目标在开头附近时，比较次数只与目标的位置有关
+        let vec: Vec<u64> = (0..1 << 20).collect();
+
+        for x in [0, 1, 2, 5, 100] {
+            let mut count = 0;
+            let result = exponential_search_by(&vec, |e| {
+                count += 1;
+                e.cmp(&x)
+            });
+
+            // 倍增和二分各约 log2(x) 次，最后确认是否相等 1 次
+            assert_eq!(result, Ok(x as usize));
+            assert!(count <= 2 * (64 - x.leading_zeros() as usize) + 3);
+        }
+    }
+}
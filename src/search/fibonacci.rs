@@ -0,0 +1,117 @@
+use super::found;
+use crate::fibonacci::Fibonacci;
+use std::cmp::Ordering;
+
+/// # 斐波那契查找
+///
+/// 与二分查找类似，但按斐波那契数而不是中点划分区间：
+/// 长度为 F(k) - 1 的区间，检查第 F(k-1) 个元素后，
+/// 剩下的两部分长度分别为 F(k-1) - 1 和 F(k-2) - 1，仍然是斐波那契数减一。
+///
+/// 只用加减法计算下标，时间复杂度 O(log n)。
+/// 斐波那契数由 [`Fibonacci`] 生成，返回值与 [`binary_search`](super::binary_search) 相同。
+///
+/// ```
+/// # use algorithm_study::search::fibonacci_search;
+/// #
+/// let vec = vec![1, 2, 4, 4, 4, 7, 8, 10, 12, 13];
+///
+/// assert_eq!(fibonacci_search(&vec, &4), Ok(2));
+/// assert_eq!(fibonacci_search(&vec, &13), Ok(9));
+/// assert_eq!(fibonacci_search(&vec, &9), Err(7));
+/// assert_eq!(fibonacci_search(&vec, &0), Err(0));
+/// ```
+///
+pub fn fibonacci_search<T>(vec: &[T], x: &T) -> Result<usize, usize>
+where
+    T: Ord,
+{
+    fibonacci_search_by(vec, |e| e.cmp(x))
+}
+
+/// # 斐波那契查找（自定义比较）
+///
+/// 与 [`fibonacci_search`] 相同，但用 `f` 比较元素与目标，
+/// 含义与 [`lower_bound_by`](super::lower_bound_by) 相同。
+///
+/// ```
+/// # use algorithm_study::search::fibonacci_search_by;
+/// #
+/// let vec = vec![9, 7, 7, 4, 1];
+///
+/// assert_eq!(fibonacci_search_by(&vec, |e| 7.cmp(e)), Ok(1));
+/// assert_eq!(fibonacci_search_by(&vec, |e| 5.cmp(e)), Err(3));
+/// ```
+///
+pub fn fibonacci_search_by<T, F>(vec: &[T], mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let len = vec.len() as u128;
+
+    // 找到最小的 F(k) >= len + 1，(a, b) = (F(k-1), F(k))
+    let mut fib = Fibonacci::new();
+    let (mut a, mut b) = (0, 1);
+    while b < len + 1 {
+        a = b;
+        b = fib.next().unwrap();
+    }
+
+    // 结果在 vec[offset..offset + b - 1] 与其末尾之间，offset 之前的元素都小于目标；
+    // 超出数组的位置看作大于目标
+    let mut offset = 0;
+    while b > 1 {
+        let i = offset + a as usize - 1;
+        if i < vec.len() && f(&vec[i]) == Ordering::Less {
+            // 跳过前 F(k-1) 个元素，剩下 F(k-2) - 1 个
+            offset = i + 1;
+            (a, b) = (2 * a - b, b - a);
+        } else {
+            // 保留前 F(k-1) - 1 个元素
+            (a, b) = (b - a, a);
+        }
+    }
+
+    found(vec, offset, f)
+}
+
+/// # 斐波那契查找（按键查找）
+///
+/// 与 [`fibonacci_search`] 相同，但比较 `f` 提取的键与 `key`。
+///
+/// ```
+/// # use algorithm_study::search::fibonacci_search_by_key;
+/// #
+/// let vec = vec![("a", 1), ("b", 3), ("c", 3), ("d", 5)];
+///
+/// assert_eq!(fibonacci_search_by_key(&vec, &3, |x| x.1), Ok(1));
+/// ```
+///
+pub fn fibonacci_search_by_key<T, K, F>(vec: &[T], key: &K, mut f: F) -> Result<usize, usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    fibonacci_search_by(vec, |e| f(e).cmp(key))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_all_positions() {
+        // 长度取斐波那契数附近的值，检查每个元素以及每个空隙
+        for len in [
+            0, 1, 2, 3, 4, 5, 7, 8, 9, 12, 13, 14, 20, 21, 22, 33, 34, 35,
+        ] {
+            let vec: Vec<usize> = (0..len).map(|i| 2 * i + 1).collect();
+
+            for i in 0..len {
+                assert_eq!(fibonacci_search(&vec, &(2 * i + 1)), Ok(i));
+                assert_eq!(fibonacci_search(&vec, &(2 * i)), Err(i));
+            }
+            assert_eq!(fibonacci_search(&vec, &(2 * len)), Err(len));
+        }
+    }
+}
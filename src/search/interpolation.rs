@@ -0,0 +1,156 @@
+use super::found;
+
+/// # 插值查找的键
+///
+/// [`interpolation_search`] 按键之间的距离估计目标的位置，
+/// 只需要两个键之差的近似值；是否相等、谁大谁小仍然按 `Ord` 精确比较。
+///
+/// 已为所有整数类型实现，包括 `usize`、`isize`、`u128` 和 `i128`。
+///
+/// ```
+/// # use algorithm_study::search::InterpolationKey;
+/// #
+/// assert_eq!(10u8.distance(3), 7.0);
+/// assert_eq!(i64::MAX.distance(i64::MIN), u64::MAX as f64);
+/// ```
+///
+pub trait InterpolationKey: Copy + Ord {
+    /// `self` 与 `other` 之差的绝对值，不会溢出。
+    fn distance(self, other: Self) -> f64;
+}
+
+macro_rules! impl_interpolation_key {
+    ($($t:ty),*) => {$(
+        impl InterpolationKey for $t {
+            fn distance(self, other: Self) -> f64 {
+                self.abs_diff(other) as f64
+            }
+        }
+    )*};
+}
+
+impl_interpolation_key!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// # 插值查找
+///
+/// 与二分查找类似，但不取区间的中点，而是假设键在区间两端之间均匀分布，
+/// 按 `x` 在两端键值之间的比例估计它的位置，就像在字典里按首字母翻页。
+///
+/// 键均匀分布时平均只需 O(log log n) 次比较；分布很不均匀时，每次只能排除很少的元素，
+/// 最坏 O(n)。键需要实现 [`InterpolationKey`]，返回值与
+/// [`binary_search`](super::binary_search) 相同。
+///
+/// ```
+/// # use algorithm_study::search::interpolation_search;
+/// #
+/// let vec: Vec<usize> = (0..1000).map(|x| x * 3).collect();
+///
+/// assert_eq!(interpolation_search(&vec, &300), Ok(100));
+/// assert_eq!(interpolation_search(&vec, &301), Err(101));
+/// assert_eq!(interpolation_search(&vec, &5000), Err(1000));
+///
+/// let vec = vec![-5i64, 0, 0, 0, 8];
+/// assert_eq!(interpolation_search(&vec, &0), Ok(1));
+/// ```
+///
+pub fn interpolation_search<T>(vec: &[T], x: &T) -> Result<usize, usize>
+where
+    T: InterpolationKey,
+{
+    interpolation_search_by_key(vec, x, |&e| e)
+}
+
+/// # 插值查找（按键查找）
+///
+/// 与 [`interpolation_search`] 相同，但比较 `f` 提取的键与 `key`。
+///
+/// ```
+/// # use algorithm_study::search::interpolation_search_by_key;
+/// #
+/// let vec = vec![("a", 10u8), ("b", 20), ("c", 20), ("d", 250)];
+///
+/// assert_eq!(interpolation_search_by_key(&vec, &20, |x| x.1), Ok(1));
+/// assert_eq!(interpolation_search_by_key(&vec, &100, |x| x.1), Err(3));
+/// ```
+///
+pub fn interpolation_search_by_key<T, K, F>(vec: &[T], key: &K, mut f: F) -> Result<usize, usize>
+where
+    K: InterpolationKey,
+    F: FnMut(&T) -> K,
+{
+    let x = *key;
+    let mut key_at = |i: usize| f(&vec[i]);
+
+    // 结果在 [lo, hi] 中：lo 之前的键都小于 x，hi 及之后的键都不小于 x
+    let (mut lo, mut hi) = (0, vec.len());
+    while lo < hi {
+        let (first, last) = (key_at(lo), key_at(hi - 1));
+        if x <= first {
+            break;
+        }
+        if x > last {
+            lo = hi;
+            break;
+        }
+
+        // first < x <= last，按比例估计 x 的位置，用浮点数避免乘法溢出
+        let ratio = x.distance(first) / last.distance(first);
+        let pos = lo + ((ratio * (hi - 1 - lo) as f64) as usize).min(hi - 1 - lo);
+
+        if key_at(pos) < x {
+            lo = pos + 1;
+        } else {
+            hi = pos;
+        }
+    }
+
+    found(vec, lo, |e| f(e).cmp(&x))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_skewed() {
+        // 键分布很不均匀时结果仍然正确
+        let vec: Vec<u64> = (0..64).map(|i| 1 << i).collect();
+
+        for (i, &e) in vec.iter().enumerate() {
+            assert_eq!(interpolation_search(&vec, &e), Ok(i));
+            if e > 1 {
+                assert_eq!(interpolation_search(&vec, &(e + 1)), Err(i + 1));
+            }
+        }
+        assert_eq!(interpolation_search(&vec, &0), Err(0));
+        assert_eq!(interpolation_search(&vec, &u64::MAX), Err(64));
+    }
+
+    #[test]
+    fn test_extreme_keys() {
+        let vec = vec![i64::MIN, i64::MIN, -1, 0, i64::MAX];
+
+        assert_eq!(interpolation_search(&vec, &i64::MIN), Ok(0));
+        assert_eq!(interpolation_search(&vec, &0), Ok(3));
+        assert_eq!(interpolation_search(&vec, &1), Err(4));
+        assert_eq!(interpolation_search(&vec, &i64::MAX), Ok(4));
+    }
+
+    #[test]
+    fn test_wide_keys() {
+        // 超出 i128 的范围，以及 usize、isize 这类下标
+        let vec = vec![0u128, 1, u128::MAX / 2, u128::MAX - 1, u128::MAX];
+        for (i, e) in vec.iter().enumerate() {
+            assert_eq!(interpolation_search(&vec, e), Ok(i));
+        }
+        assert_eq!(interpolation_search(&vec, &2), Err(2));
+
+        let vec: Vec<usize> = (0..100).map(|i| i * i).collect();
+        assert_eq!(interpolation_search(&vec, &2500), Ok(50));
+        assert_eq!(interpolation_search(&vec, &usize::MAX), Err(100));
+
+        let vec = vec![isize::MIN, -1, 0, isize::MAX];
+        assert_eq!(interpolation_search(&vec, &-1), Ok(1));
+        assert_eq!(interpolation_search(&vec, &1), Err(3));
+    }
+}
@@ -0,0 +1,369 @@
+use std::cmp::Ordering;
+use std::ops::Range;
+
+mod exponential;
+mod fibonacci;
+mod interpolation;
+
+pub use exponential::{exponential_search, exponential_search_by, exponential_search_by_key};
+pub use fibonacci::{fibonacci_search, fibonacci_search_by, fibonacci_search_by_key};
+pub use interpolation::{interpolation_search, interpolation_search_by_key, InterpolationKey};
+
+// 本模块的查找函数都要求 `vec` 已经按查找时使用的顺序升序排列，否则结果没有意义。
+//
+// 遇到重复的键时语义一致：
+//
+// - `lower_bound` 返回第一个不小于目标的位置，`upper_bound` 返回第一个大于目标的位置；
+// - `*_search` 找到时返回 `Ok(第一个等于目标的位置)`，
+//   找不到时返回 `Err(插入位置)`，即 `lower_bound` 的结果。
+//
+// 标准库的 `binary_search` 遇到重复的键时可能返回其中任意一个。
+
+/// # 分界点
+///
+/// `vec` 的前一部分满足 `pred`，后一部分不满足，返回后一部分的起点，
+/// 即第一个不满足 `pred` 的位置；都满足时返回 `vec.len()`。
+///
+/// 每次取区间的中点判断，把区间缩小一半，时间复杂度 O(log n)。
+/// 其他二分查找都由它实现。
+///
+/// ```
+/// # use algorithm_study::search::partition_point;
+/// #
+/// let vec = vec![1, 2, 3, 3, 5, 6, 7];
+///
+/// assert_eq!(partition_point(&vec, |&x| x < 5), 4);
+/// assert_eq!(partition_point(&vec, |&x| x < 100), 7);
+/// assert_eq!(partition_point(&vec, |_| false), 0);
+/// ```
+///
+pub fn partition_point<T, P>(vec: &[T], mut pred: P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    // 分界点在 [lo, hi] 中：lo 之前的都满足，hi 及之后的都不满足
+    let (mut lo, mut hi) = (0, vec.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(&vec[mid]) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// # 下界
+///
+/// 返回第一个不小于 `x` 的位置，所有元素都小于 `x` 时返回 `vec.len()`。
+/// 把 `x` 插入到这里，数组仍然有序，并且排在与它相等的元素之前。
+///
+/// ```
+/// # use algorithm_study::search::lower_bound;
+/// #
+/// let vec = vec![1, 2, 4, 4, 4, 7];
+///
+/// assert_eq!(lower_bound(&vec, &4), 2);
+/// assert_eq!(lower_bound(&vec, &3), 2);
+/// assert_eq!(lower_bound(&vec, &0), 0);
+/// assert_eq!(lower_bound(&vec, &9), 6);
+/// ```
+///
+pub fn lower_bound<T>(vec: &[T], x: &T) -> usize
+where
+    T: Ord,
+{
+    lower_bound_by(vec, |e| e.cmp(x))
+}
+
+/// # 下界（自定义比较）
+///
+/// 与 [`lower_bound`] 相同，但用 `f` 比较元素与目标：
+/// 元素小于、等于、大于目标时，分别返回 `Less`、`Equal`、`Greater`。
+///
+/// ```
+/// # use algorithm_study::search::lower_bound_by;
+/// #
+/// let vec = vec![7, 4, 4, 2];
+///
+/// assert_eq!(lower_bound_by(&vec, |e| 4.cmp(e)), 1);
+/// ```
+///
+pub fn lower_bound_by<T, F>(vec: &[T], mut f: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    partition_point(vec, |e| f(e) == Ordering::Less)
+}
+
+/// # 下界（按键查找）
+///
+/// 与 [`lower_bound`] 相同，但比较 `f` 提取的键与 `key`。
+///
+/// ```
+/// # use algorithm_study::search::lower_bound_by_key;
+/// #
+/// let vec = vec![("a", 1), ("b", 3), ("c", 3), ("d", 5)];
+///
+/// assert_eq!(lower_bound_by_key(&vec, &3, |x| x.1), 1);
+/// ```
+///
+pub fn lower_bound_by_key<T, K, F>(vec: &[T], key: &K, mut f: F) -> usize
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    lower_bound_by(vec, |e| f(e).cmp(key))
+}
+
+/// # 上界
+///
+/// 返回第一个大于 `x` 的位置，没有大于 `x` 的元素时返回 `vec.len()`。
+/// 把 `x` 插入到这里，数组仍然有序，并且排在与它相等的元素之后。
+///
+/// ```
+/// # use algorithm_study::search::upper_bound;
+/// #
+/// let vec = vec![1, 2, 4, 4, 4, 7];
+///
+/// assert_eq!(upper_bound(&vec, &4), 5);
+/// assert_eq!(upper_bound(&vec, &3), 2);
+/// assert_eq!(upper_bound(&vec, &7), 6);
+/// ```
+///
+pub fn upper_bound<T>(vec: &[T], x: &T) -> usize
+where
+    T: Ord,
+{
+    upper_bound_by(vec, |e| e.cmp(x))
+}
+
+/// # 上界（自定义比较）
+///
+/// 与 [`upper_bound`] 相同，但用 `f` 比较元素与目标，含义与 [`lower_bound_by`] 相同。
+///
+/// ```
+/// # use algorithm_study::search::upper_bound_by;
+/// #
+/// let vec = vec![7, 4, 4, 2];
+///
+/// assert_eq!(upper_bound_by(&vec, |e| 4.cmp(e)), 3);
+/// ```
+///
+pub fn upper_bound_by<T, F>(vec: &[T], mut f: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    partition_point(vec, |e| f(e) != Ordering::Greater)
+}
+
+/// # 上界（按键查找）
+///
+/// 与 [`upper_bound`] 相同，但比较 `f` 提取的键与 `key`。
+///
+/// ```
+/// # use algorithm_study::search::upper_bound_by_key;
+/// #
+/// let vec = vec![("a", 1), ("b", 3), ("c", 3), ("d", 5)];
+///
+/// assert_eq!(upper_bound_by_key(&vec, &3, |x| x.1), 3);
+/// ```
+///
+pub fn upper_bound_by_key<T, K, F>(vec: &[T], key: &K, mut f: F) -> usize
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    upper_bound_by(vec, |e| f(e).cmp(key))
+}
+
+/// # 相等的范围
+///
+/// 返回所有等于 `x` 的元素所在的范围，即 [`lower_bound`]`..`[`upper_bound`]。
+/// 没有等于 `x` 的元素时，范围为空，起点是 `x` 的插入位置。
+///
+/// ```
+/// # use algorithm_study::search::equal_range;
+/// #
+/// let vec = vec![1, 2, 4, 4, 4, 7];
+///
+/// assert_eq!(equal_range(&vec, &4), 2..5);
+/// assert_eq!(equal_range(&vec, &5), 5..5);
+/// assert_eq!(vec[equal_range(&vec, &4)], [4, 4, 4]);
+/// ```
+///
+pub fn equal_range<T>(vec: &[T], x: &T) -> Range<usize>
+where
+    T: Ord,
+{
+    equal_range_by(vec, |e| e.cmp(x))
+}
+
+/// # 相等的范围（自定义比较）
+///
+/// 与 [`equal_range`] 相同，但用 `f` 比较元素与目标，含义与 [`lower_bound_by`] 相同。
+///
+/// ```
+/// # use algorithm_study::search::equal_range_by;
+/// #
+/// let vec = vec![7, 4, 4, 2];
+///
+/// assert_eq!(equal_range_by(&vec, |e| 4.cmp(e)), 1..3);
+/// ```
+///
+pub fn equal_range_by<T, F>(vec: &[T], mut f: F) -> Range<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    // 上界只需在下界之后查找
+    let start = lower_bound_by(vec, &mut f);
+    let end = start + upper_bound_by(&vec[start..], f);
+    start..end
+}
+
+/// # 相等的范围（按键查找）
+///
+/// 与 [`equal_range`] 相同，但比较 `f` 提取的键与 `key`。
+///
+/// ```
+/// # use algorithm_study::search::equal_range_by_key;
+/// #
+/// let vec = vec![("a", 1), ("b", 3), ("c", 3), ("d", 5)];
+///
+/// assert_eq!(equal_range_by_key(&vec, &3, |x| x.1), 1..3);
+/// ```
+///
+pub fn equal_range_by_key<T, K, F>(vec: &[T], key: &K, mut f: F) -> Range<usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    equal_range_by(vec, |e| f(e).cmp(key))
+}
+
+/// # 二分查找
+///
+/// 找到时返回 `Ok(i)`，`i` 是第一个等于 `x` 的位置；
+/// 找不到时返回 `Err(i)`，`i` 是 `x` 的插入位置。两种情况下 `i` 都等于 [`lower_bound`] 的结果。
+///
+/// ```
+/// # use algorithm_study::search::binary_search;
+/// #
+/// let vec = vec![1, 2, 4, 4, 4, 7];
+///
+/// assert_eq!(binary_search(&vec, &4), Ok(2));
+/// assert_eq!(binary_search(&vec, &7), Ok(5));
+/// assert_eq!(binary_search(&vec, &3), Err(2));
+/// assert_eq!(binary_search(&vec, &9), Err(6));
+/// ```
+///
+pub fn binary_search<T>(vec: &[T], x: &T) -> Result<usize, usize>
+where
+    T: Ord,
+{
+    binary_search_by(vec, |e| e.cmp(x))
+}
+
+/// # 二分查找（自定义比较）
+///
+/// 与 [`binary_search`] 相同，但用 `f` 比较元素与目标，含义与 [`lower_bound_by`] 相同。
+///
+/// ```
+/// # use algorithm_study::search::binary_search_by;
+/// #
+/// let vec = vec!["a", "bb", "cc", "dddd"];
+///
+/// assert_eq!(binary_search_by(&vec, |e| e.len().cmp(&2)), Ok(1));
+/// assert_eq!(binary_search_by(&vec, |e| e.len().cmp(&3)), Err(3));
+/// ```
+///
+pub fn binary_search_by<T, F>(vec: &[T], mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let i = lower_bound_by(vec, &mut f);
+    found(vec, i, f)
+}
+
+/// # 二分查找（按键查找）
+///
+/// 与 [`binary_search`] 相同，但比较 `f` 提取的键与 `key`。
+///
+/// ```
+/// # use algorithm_study::search::binary_search_by_key;
+/// #
+/// let vec = vec![("a", 1), ("b", 3), ("c", 3), ("d", 5)];
+///
+/// assert_eq!(binary_search_by_key(&vec, &3, |x| x.1), Ok(1));
+/// assert_eq!(binary_search_by_key(&vec, &4, |x| x.1), Err(3));
+/// ```
+///
+pub fn binary_search_by_key<T, K, F>(vec: &[T], key: &K, mut f: F) -> Result<usize, usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    binary_search_by(vec, |e| f(e).cmp(key))
+}
+
+/// 已知 `i` 是下界，判断 `vec[i]` 是否等于目标
+fn found<T, F>(vec: &[T], i: usize, mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    match vec.get(i) {
+        Some(e) if f(e) == Ordering::Equal => Ok(i),
+        _ => Err(i),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::random;
+
+    #[test]
+    fn test_bounds() {
+        let mut seed = 1;
+
+        for len in [0, 1, 2, 3, 10, 100, 1000] {
+            let mut vec: Vec<u32> = (0..len).map(|_| random(&mut seed) as u32 % 50).collect();
+            vec.sort();
+
+            for x in 0..=50 {
+                let lower = vec.iter().filter(|&&e| e < x).count();
+                let upper = vec.iter().filter(|&&e| e <= x).count();
+
+                assert_eq!(lower_bound(&vec, &x), lower);
+                assert_eq!(upper_bound(&vec, &x), upper);
+                assert_eq!(equal_range(&vec, &x), lower..upper);
+                assert_eq!(partition_point(&vec, |&e| e < x), lower);
+            }
+        }
+    }
+
+    #[test]
+    fn test_duplicates() {
+        // 所有查找函数在重复的键上给出相同的结果：第一个相等的位置，或者插入位置
+        let mut seed = 2;
+
+        for len in [0, 1, 2, 3, 10, 100, 1000] {
+            let mut vec: Vec<u32> = (0..len).map(|_| random(&mut seed) as u32 % 20).collect();
+            vec.sort();
+
+            for x in 0..=20 {
+                let lower = lower_bound(&vec, &x);
+                let expected = match vec.get(lower) {
+                    Some(&e) if e == x => Ok(lower),
+                    _ => Err(lower),
+                };
+
+                assert_eq!(binary_search(&vec, &x), expected);
+                assert_eq!(exponential_search(&vec, &x), expected);
+                assert_eq!(fibonacci_search(&vec, &x), expected);
+                assert_eq!(interpolation_search(&vec, &x), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,554 @@
+use super::{heap, merge_sort_slice_by};
+use std::cmp::Ordering;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{self, AtomicUsize};
+
+/// 默认的内存预算：64 MiB
+const DEFAULT_MEMORY: usize = 64 * 1024 * 1024;
+
+/// 一次最多同时合并的临时文件数，超过时分多轮合并
+const MAX_FAN_IN: usize = 64;
+
+/// 每个元素除了内容本身以外的内存开销
+const ITEM_OVERHEAD: usize = mem::size_of::<Vec<u8>>();
+
+/// 临时文件名的序号，同一进程里的多次排序不会重名
+static TEMP_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// # 外部归并排序
+///
+/// 用于排序放不进内存的大文件：
+///
+/// 1. 每次读入不超过内存预算的一块数据，在内存中用归并排序排好，写入一个临时文件；
+/// 2. 用堆对所有临时文件做多路归并，写入输出。临时文件超过 64 个时，先分轮合并：
+///    每一轮把相邻的 64 个合并成一个，每个元素每轮只读写一次。
+///
+/// 数据一次就能读完时不会创建临时文件。无论排序成功与否，临时文件都会被删除。
+/// 排序是稳定的：相等的元素保持在输入中的先后顺序。
+///
+/// 支持两种输入格式：以 `\n` 分隔的文本行（[`sort_lines`](ExternalSort::sort_lines)）
+/// 和定长的二进制记录（[`sort_records`](ExternalSort::sort_records)），默认按字节的字典序比较。
+///
+/// ```
+/// # use algorithm_study::sorting::ExternalSort;
+/// #
+/// let input = "pear\napple\nfig\nbanana\n";
+/// let mut output = Vec::new();
+///
+/// ExternalSort::new()
+///     .memory_budget(64)
+///     .temp_dir(std::env::temp_dir())
+///     .sort_lines(input.as_bytes(), &mut output)
+///     .unwrap();
+///
+/// assert_eq!(output, b"apple\nbanana\nfig\npear\n");
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct ExternalSort {
+    memory: usize,
+    temp_dir: PathBuf,
+}
+
+/// 输入输出的格式
+#[derive(Clone, Copy)]
+enum Format {
+    Lines,
+    Records(usize),
+}
+
+impl Format {
+    /// 读出下一个元素放入 `buf`，已经读完时返回 `false`
+    fn read<R: BufRead>(self, reader: &mut R, buf: &mut Vec<u8>) -> io::Result<bool> {
+        buf.clear();
+
+        match self {
+            Format::Lines => {
+                if reader.read_until(b'\n', buf)? == 0 {
+                    return Ok(false);
+                }
+                if buf.last() == Some(&b'\n') {
+                    buf.pop();
+                }
+                Ok(true)
+            }
+            Format::Records(len) => match reader.by_ref().take(len as u64).read_to_end(buf)? {
+                0 => Ok(false),
+                n if n == len => Ok(true),
+                _ => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "input ends with a partial record",
+                )),
+            },
+        }
+    }
+
+    fn write<W: Write>(self, writer: &mut W, item: &[u8]) -> io::Result<()> {
+        writer.write_all(item)?;
+        if let Format::Lines = self {
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl ExternalSort {
+    /// 创建外部排序，内存预算为 64 MiB，临时文件放在 [`std::env::temp_dir`]。
+    ///
+    /// # Examples
+    /// ---
+    /// ```
+    /// # use algorithm_study::sorting::ExternalSort;
+    /// #
+    /// let mut output = Vec::new();
+    /// ExternalSort::new().sort_lines(&b"b\na"[..], &mut output).unwrap();
+    ///
+    /// // 最后一行没有换行符时也会补上
+    /// assert_eq!(output, b"a\nb\n");
+    /// ```
+    ///
+    pub fn new() -> ExternalSort {
+        ExternalSort {
+            memory: DEFAULT_MEMORY,
+            temp_dir: env::temp_dir(),
+        }
+    }
+
+    /// 设置内存预算（字节），即每个临时文件的大致大小。
+    ///
+    /// 按元素内容加上每个元素固定的开销估算，不含排序时的辅助空间。
+    /// 每块至少包含一个元素。
+    pub fn memory_budget(mut self, bytes: usize) -> ExternalSort {
+        self.memory = bytes;
+        self
+    }
+
+    /// 设置存放临时文件的目录，目录必须已经存在。
+    pub fn temp_dir<P: Into<PathBuf>>(mut self, dir: P) -> ExternalSort {
+        self.temp_dir = dir.into();
+        self
+    }
+
+    /// 对文本行排序，按字节的字典序比较。
+    ///
+    /// 每行以 `\n` 结尾，行本身不含 `\n`；最后一行没有 `\n` 时，输出中会补上。
+    pub fn sort_lines<R, W>(&self, input: R, output: W) -> io::Result<()>
+    where
+        R: Read,
+        W: Write,
+    {
+        self.sort_impl(input, output, Format::Lines, &mut |a, b| a.cmp(b))
+    }
+
+    /// 对文本行排序，使用 `compare` 决定行的顺序。
+    ///
+    /// # Examples
+    /// ---
+    /// ```
+    /// # use algorithm_study::sorting::ExternalSort;
+    /// #
+    /// let input = "10\n9\n100\n";
+    /// let mut output = Vec::new();
+    ///
+    /// ExternalSort::new()
+    ///     .sort_lines_by(input.as_bytes(), &mut output, |a, b| a.len().cmp(&b.len()))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(output, b"9\n10\n100\n");
+    /// ```
+    ///
+    pub fn sort_lines_by<R, W, F>(&self, input: R, output: W, mut compare: F) -> io::Result<()>
+    where
+        R: Read,
+        W: Write,
+        F: FnMut(&[u8], &[u8]) -> Ordering,
+    {
+        self.sort_impl(input, output, Format::Lines, &mut compare)
+    }
+
+    /// 对长度为 `record_len` 字节的定长记录排序，按字节的字典序比较。
+    ///
+    /// 输入长度不是 `record_len` 的整数倍时返回 [`io::ErrorKind::InvalidData`]，
+    /// `record_len` 为 0 时返回 [`io::ErrorKind::InvalidInput`]。
+    ///
+    /// # Examples
+    /// ---
+    /// ```
+    /// # use algorithm_study::sorting::ExternalSort;
+    /// #
+    /// let input = [3u8, 0, 1, 9, 2, 5];
+    /// let mut output = Vec::new();
+    ///
+    /// ExternalSort::new().sort_records(&input[..], &mut output, 2).unwrap();
+    ///
+    /// assert_eq!(output, [1, 9, 2, 5, 3, 0]);
+    /// ```
+    ///
+    pub fn sort_records<R, W>(&self, input: R, output: W, record_len: usize) -> io::Result<()>
+    where
+        R: Read,
+        W: Write,
+    {
+        self.sort_records_by(input, output, record_len, |a, b| a.cmp(b))
+    }
+
+    /// 对长度为 `record_len` 字节的定长记录排序，使用 `compare` 决定记录的顺序。
+    ///
+    /// # Examples
+    /// ---
+    /// ```
+    /// # use algorithm_study::sorting::ExternalSort;
+    /// #
+    /// // 按小端序的 u16 排序
+    /// let input: Vec<u8> = [300u16, 2, 256].iter().flat_map(|x| x.to_le_bytes()).collect();
+    /// let mut output = Vec::new();
+    ///
+    /// ExternalSort::new()
+    ///     .sort_records_by(&input[..], &mut output, 2, |a, b| {
+    ///         u16::from_le_bytes([a[0], a[1]]).cmp(&u16::from_le_bytes([b[0], b[1]]))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let sorted: Vec<u16> = output.chunks(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    /// assert_eq!(sorted, vec![2, 256, 300]);
+    /// ```
+    ///
+    pub fn sort_records_by<R, W, F>(
+        &self,
+        input: R,
+        output: W,
+        record_len: usize,
+        mut compare: F,
+    ) -> io::Result<()>
+    where
+        R: Read,
+        W: Write,
+        F: FnMut(&[u8], &[u8]) -> Ordering,
+    {
+        if record_len == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "record length must be positive",
+            ));
+        }
+        self.sort_impl(input, output, Format::Records(record_len), &mut compare)
+    }
+
+    fn sort_impl<R, W, F>(
+        &self,
+        input: R,
+        output: W,
+        format: Format,
+        compare: &mut F,
+    ) -> io::Result<()>
+    where
+        R: Read,
+        W: Write,
+        F: FnMut(&[u8], &[u8]) -> Ordering,
+    {
+        let mut input = BufReader::new(input);
+        let mut output = BufWriter::new(output);
+        let mut runs = TempRuns::new(&self.temp_dir);
+
+        // 分块读入、排序，写入临时文件
+        loop {
+            let (mut chunk, done) = self.read_chunk(&mut input, format)?;
+            merge_sort_slice_by(&mut chunk, |a, b| compare(a, b));
+
+            // 全部数据一次就读完了，不需要临时文件
+            if done && runs.paths.is_empty() {
+                for item in &chunk {
+                    format.write(&mut output, item)?;
+                }
+                return output.flush();
+            }
+
+            if !chunk.is_empty() {
+                let mut writer = runs.create()?;
+                for item in &chunk {
+                    format.write(&mut writer, item)?;
+                }
+                writer.flush()?;
+            }
+
+            if done {
+                break;
+            }
+        }
+
+        // 临时文件太多时分轮合并：每一轮把相邻的 MAX_FAN_IN 个文件合并成一个，
+        // 新文件按各组原来的顺序排列，保持稳定。每一轮每个元素只读写一次
+        while runs.paths.len() > MAX_FAN_IN {
+            let count = runs.paths.len();
+            let mut start = 0;
+            while start + 1 < count {
+                let end = count.min(start + MAX_FAN_IN);
+                let mut writer = runs.create()?;
+                merge_runs(&runs.paths[start..end], format, compare, &mut writer)?;
+                writer.flush()?;
+                start = end;
+            }
+
+            let mut done: Vec<PathBuf> = runs.paths.drain(..count).collect();
+            // 最后一组只有一个文件时不用合并，直接留到下一轮
+            if start < count {
+                runs.paths.extend(done.pop());
+            }
+            for path in done {
+                fs::remove_file(path)?;
+            }
+        }
+
+        merge_runs(&runs.paths, format, compare, &mut output)?;
+        output.flush()
+    }
+
+    /// 读入不超过内存预算的一块数据，返回这些元素以及输入是否已经读完
+    fn read_chunk<R: BufRead>(
+        &self,
+        input: &mut R,
+        format: Format,
+    ) -> io::Result<(Vec<Vec<u8>>, bool)> {
+        let mut chunk = Vec::new();
+        let mut used = 0;
+
+        while used < self.memory || chunk.is_empty() {
+            let mut item = Vec::new();
+            if !format.read(input, &mut item)? {
+                return Ok((chunk, true));
+            }
+            used += item.len() + ITEM_OVERHEAD;
+            chunk.push(item);
+        }
+
+        Ok((chunk, false))
+    }
+}
+
+impl Default for ExternalSort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 多路归并若干个有序的临时文件，写入 `output`。
+///
+/// 堆中存放每个文件的当前元素和文件序号，堆顶是最先输出的元素：
+/// 内容更小的，或内容相等但来自更早的文件的。
+fn merge_runs<F, W>(
+    paths: &[PathBuf],
+    format: Format,
+    compare: &mut F,
+    output: &mut W,
+) -> io::Result<()>
+where
+    F: FnMut(&[u8], &[u8]) -> Ordering,
+    W: Write,
+{
+    let mut readers = paths
+        .iter()
+        .map(|path| File::open(path).map(BufReader::new))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut heap: Vec<(Vec<u8>, usize)> = Vec::with_capacity(readers.len());
+    for (i, reader) in readers.iter_mut().enumerate() {
+        let mut item = Vec::new();
+        if format.read(reader, &mut item)? {
+            heap.push((item, i));
+        }
+    }
+
+    // 堆顶是“最大”的元素，因此反过来比较
+    let is_less = &mut |a: &(Vec<u8>, usize), b: &(Vec<u8>, usize)| match compare(&b.0, &a.0) {
+        Ordering::Equal => b.1 < a.1,
+        ord => ord == Ordering::Less,
+    };
+    heap::heapify(&mut heap, is_less, &());
+
+    while !heap.is_empty() {
+        format.write(output, &heap[0].0)?;
+
+        // 从同一个文件补上下一个元素，文件读完就把它移出堆
+        let (item, i) = &mut heap[0];
+        if !format.read(&mut readers[*i], item)? {
+            heap.swap_remove(0);
+        }
+        heap::sift_down(&mut heap, 0, is_less, &());
+    }
+
+    Ok(())
+}
+
+/// 按顺序排列的临时文件，drop 时删除所有还在的文件
+struct TempRuns<'a> {
+    dir: &'a Path,
+    paths: Vec<PathBuf>,
+}
+
+impl<'a> TempRuns<'a> {
+    fn new(dir: &'a Path) -> Self {
+        TempRuns {
+            dir,
+            paths: Vec::new(),
+        }
+    }
+
+    /// 在末尾新建一个临时文件
+    fn create(&mut self) -> io::Result<BufWriter<File>> {
+        let id = TEMP_ID.fetch_add(1, atomic::Ordering::Relaxed);
+        let path = self
+            .dir
+            .join(format!("external-sort-{}-{}.tmp", process::id(), id));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        self.paths.push(path);
+        Ok(BufWriter::new(file))
+    }
+}
+
+impl Drop for TempRuns<'_> {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::random;
+
+    /// 每个测试使用单独的临时目录，便于检查临时文件是否删干净
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("external-sort-test-{}-{}", process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn assert_empty(dir: &Path) {
+        assert_eq!(0, fs::read_dir(dir).unwrap().count());
+        fs::remove_dir(dir).unwrap();
+    }
+
+    #[test]
+    fn test_lines() {
+        let dir = test_dir("lines");
+        let mut seed = 37;
+        let lines: Vec<String> = (0..5000)
+            .map(|_| (random(&mut seed) % 100_000).to_string())
+            .collect();
+        let input = lines.join("\n");
+
+        let mut expected = lines.clone();
+        expected.sort();
+        let expected: String = expected.iter().map(|l| format!("{}\n", l)).collect();
+
+        // 预算很小时有上百个临时文件，需要分批合并；预算足够时不用临时文件
+        for memory in [0, 200, 5000, usize::MAX] {
+            let mut output = Vec::new();
+            ExternalSort::new()
+                .memory_budget(memory)
+                .temp_dir(&dir)
+                .sort_lines(input.as_bytes(), &mut output)
+                .unwrap();
+            assert_eq!(expected.as_bytes(), &output[..]);
+        }
+
+        assert_empty(&dir);
+    }
+
+    #[test]
+    fn test_stability() {
+        let dir = test_dir("stability");
+        let mut seed = 41;
+        let records: Vec<[u8; 4]> = (0..3000u16)
+            .map(|i| {
+                let [a, b] = i.to_be_bytes();
+                [(random(&mut seed) % 10) as u8, 0, a, b]
+            })
+            .collect();
+        let input: Vec<u8> = records.concat();
+
+        // 只比较第一个字节，相等的记录按输入顺序排列
+        let mut expected = records.clone();
+        expected.sort_by_key(|r| r[0]);
+
+        let mut output = Vec::new();
+        ExternalSort::new()
+            .memory_budget(300)
+            .temp_dir(&dir)
+            .sort_records_by(&input[..], &mut output, 4, |a, b| a[0].cmp(&b[0]))
+            .unwrap();
+        assert_eq!(expected.concat(), output);
+
+        assert_empty(&dir);
+    }
+
+    #[test]
+    fn test_many_runs() {
+        let dir = test_dir("many-runs");
+        let mut seed = 43;
+
+        // 预算为 0 时每个记录一个临时文件：最后一组只剩一个文件、需要合并两轮等情况
+        for len in [65u16, 129, 200, 4097] {
+            let records: Vec<[u8; 3]> = (0..len)
+                .map(|i| {
+                    let [a, b] = i.to_be_bytes();
+                    [(random(&mut seed) % 4) as u8, a, b]
+                })
+                .collect();
+            let mut expected = records.clone();
+            expected.sort_by_key(|r| r[0]);
+
+            let mut output = Vec::new();
+            ExternalSort::new()
+                .memory_budget(0)
+                .temp_dir(&dir)
+                .sort_records_by(&records.concat()[..], &mut output, 3, |a, b| {
+                    a[0].cmp(&b[0])
+                })
+                .unwrap();
+            assert_eq!(expected.concat(), output, "len = {}", len);
+        }
+
+        assert_empty(&dir);
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = test_dir("errors");
+        let sort = ExternalSort::new().memory_budget(16).temp_dir(&dir);
+
+        let err = sort
+            .sort_records(&[1u8, 2, 3][..], Vec::new(), 2)
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+
+        let err = sort.sort_records(&[1u8][..], Vec::new(), 0).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+
+        // 读到一半出错时，已经写出的临时文件也会被删除
+        let failing = (&b"b\na\nc\nd\ne\nf\n"[..]).chain(FailingReader);
+        let err = sort.sort_lines(failing, Vec::new()).unwrap_err();
+        assert_eq!(io::ErrorKind::Other, err.kind());
+
+        assert_empty(&dir);
+    }
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken input"))
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use super::{radix_sort_lsd, RadixKey};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+/// # 浮点数
+///
+/// 为 `f32` 和 `f64` 实现，给出 IEEE 754 的 totalOrder 全序，
+/// 同时实现了 [`RadixKey`]，可以直接用 [`radix_sort_lsd`] 等基数排序。
+///
+/// totalOrder 的顺序为：负 NaN < -∞ < 负数 < -0.0 < +0.0 < 正数 < +∞ < 正 NaN。
+///
+/// ```
+/// # use algorithm_study::sorting::radix_sort_lsd;
+/// #
+/// let mut vec = vec![2.5, -0.0, f64::INFINITY, -1.0, 0.0, f64::NEG_INFINITY];
+///
+/// radix_sort_lsd(&mut vec);
+///
+/// assert_eq!(format!("{:?}", vec), "[-inf, -1.0, -0.0, 0.0, 2.5, inf]");
+/// ```
+///
+pub trait Float: Copy + RadixKey {
+    /// 按 totalOrder 比较。
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    /// 是否是 NaN。
+    fn is_nan(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($($t:ty => $u:ty),*) => {$(
+        impl Float for $t {
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                <$t>::total_cmp(self, other)
+            }
+
+            fn is_nan(self) -> bool {
+                <$t>::is_nan(self)
+            }
+        }
+
+        impl RadixKey for $t {
+            fn key_len(&self) -> usize {
+                std::mem::size_of::<$t>()
+            }
+
+            fn key_byte(&self, level: usize) -> Option<u8> {
+                // 负数翻转所有位，绝对值越大越小；非负数只翻转符号位，排在负数之后
+                let bits = self.to_bits();
+                let sign = 1 << (<$u>::BITS - 1);
+                let flipped = if bits & sign != 0 { !bits } else { bits | sign };
+                flipped.to_be_bytes().get(level).copied()
+            }
+        }
+    )*};
+}
+
+impl_float!(f32 => u32, f64 => u64);
+
+/// # 全序浮点数
+///
+/// 按 IEEE 754 的 totalOrder 实现 `Ord` 的包装，用于需要 `Ord` 的排序函数、
+/// `BTreeMap` 的键等。相等当且仅当两个数的二进制表示相同，因此 `-0.0` 与 `0.0` 不相等，
+/// 同一个 NaN 与自身相等。
+///
+/// ```
+/// # use algorithm_study::sorting::{quick_sort_by_key, TotalOrd};
+/// #
+/// let mut vec = vec![1.5, f64::NAN, -3.0, 0.0];
+///
+/// quick_sort_by_key(&mut vec, |&x| TotalOrd(x));
+///
+/// assert_eq!(&vec[..3], &[-3.0, 0.0, 1.5]);
+/// assert!(vec[3].is_nan());
+/// assert!(TotalOrd(-0.0) < TotalOrd(0.0));
+/// ```
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TotalOrd<F>(pub F);
+
+impl<F: Float> PartialEq for TotalOrd<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<F: Float> Eq for TotalOrd<F> {}
+
+impl<F: Float> PartialOrd for TotalOrd<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float> Ord for TotalOrd<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl<F: Float> RadixKey for TotalOrd<F> {
+    fn key_len(&self) -> usize {
+        self.0.key_len()
+    }
+
+    fn key_byte(&self, level: usize) -> Option<u8> {
+        self.0.key_byte(level)
+    }
+}
+
+/// # NaN 的处理方式
+///
+/// 见 [`float_sort`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// 所有 NaN 排在最前面
+    First,
+    /// 所有 NaN 排在最后面
+    Last,
+    /// 遇到 NaN 时返回错误，不修改数组
+    Error,
+}
+
+/// # 数组中含有 NaN
+///
+/// [`float_sort`] 使用 [`NanPolicy::Error`] 时返回，`index` 是第一个 NaN 的下标。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NanError {
+    pub index: usize,
+}
+
+impl fmt::Display for NanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NaN found at index {}", self.index)
+    }
+}
+
+impl Error for NanError {}
+
+/// # 浮点数排序
+///
+/// 用 LSD 基数排序把浮点数按数值升序排列，时间复杂度 O(n)，`-0.0` 排在 `0.0` 之前。
+///
+/// NaN 不论符号都按 `nan` 处理：放在最前面、最后面，或者返回 [`NanError`]。
+/// 多个 NaN 之间的顺序不确定。需要完整的 totalOrder（负 NaN 在最前、正 NaN 在最后）时，
+/// 直接用 [`radix_sort_lsd`](super::radix_sort_lsd) 排序即可。
+///
+/// ```
+/// # use algorithm_study::sorting::{float_sort, NanError, NanPolicy};
+/// #
+/// let mut vec = vec![2.0, f32::NAN, -1.0, 0.5];
+///
+/// float_sort(&mut vec, NanPolicy::Last).unwrap();
+/// assert_eq!(&vec[..3], &[-1.0, 0.5, 2.0]);
+/// assert!(vec[3].is_nan());
+///
+/// float_sort(&mut vec, NanPolicy::First).unwrap();
+/// assert!(vec[0].is_nan());
+/// assert_eq!(&vec[1..], &[-1.0, 0.5, 2.0]);
+///
+/// let mut vec = vec![1.0, -f64::NAN, 0.0];
+/// assert_eq!(float_sort(&mut vec, NanPolicy::Error), Err(NanError { index: 1 }));
+/// assert_eq!(vec[0], 1.0);
+/// ```
+///
+pub fn float_sort<F: Float>(vec: &mut [F], nan: NanPolicy) -> Result<(), NanError> {
+    let numbers = match nan {
+        NanPolicy::Error => {
+            if let Some(index) = vec.iter().position(|x| x.is_nan()) {
+                return Err(NanError { index });
+            }
+            vec
+        }
+        NanPolicy::First => {
+            let nans = partition_nan(vec, true);
+            &mut vec[nans..]
+        }
+        NanPolicy::Last => {
+            let numbers = partition_nan(vec, false);
+            &mut vec[..numbers]
+        }
+    };
+
+    radix_sort_lsd(numbers);
+    Ok(())
+}
+
+/// 把 NaN 换到数组前面（`nan_first`）或后面，返回前一段的长度
+fn partition_nan<F: Float>(vec: &mut [F], nan_first: bool) -> usize {
+    let mut len = 0;
+    for i in 0..vec.len() {
+        if vec[i].is_nan() == nan_first {
+            vec.swap(len, i);
+            len += 1;
+        }
+    }
+    len
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::{radix_sort_msd, random};
+
+    /// 随机的 f64，包括各种特殊值
+    fn floats(len: usize, seed: &mut u64) -> Vec<f64> {
+        const SPECIAL: [f64; 9] = [
+            0.0,
+            -0.0,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::MIN_POSITIVE,
+            -f64::MIN_POSITIVE / 4.0,
+            f64::MAX,
+            f64::NAN,
+            -f64::NAN,
+        ];
+
+        (0..len)
+            .map(|_| match random(seed) % 4 {
+                0 => SPECIAL[random(seed) as usize % SPECIAL.len()],
+                1 => f64::from_bits(random(seed) << 16 | random(seed) & 0xffff),
+                _ => (random(seed) % 2000) as f64 / 8.0 - 125.0,
+            })
+            .collect()
+    }
+
+    fn bits(vec: &[f64]) -> Vec<u64> {
+        vec.iter().map(|x| x.to_bits()).collect()
+    }
+
+    #[test]
+    fn test_total_order() {
+        let mut seed = 7;
+
+        for len in [0, 1, 2, 10, 100, 1000] {
+            let vec = floats(len, &mut seed);
+            let mut expected = vec.clone();
+            expected.sort_by(f64::total_cmp);
+
+            let mut sorted = vec.clone();
+            radix_sort_lsd(&mut sorted);
+            assert_eq!(bits(&sorted), bits(&expected));
+
+            let mut sorted = vec.clone();
+            radix_sort_msd(&mut sorted);
+            assert_eq!(bits(&sorted), bits(&expected));
+
+            let mut sorted: Vec<_> = vec.iter().map(|&x| TotalOrd(x as f32)).collect();
+            sorted.sort();
+            let mut expected: Vec<_> = vec.iter().map(|&x| x as f32).collect();
+            expected.sort_by(f32::total_cmp);
+            assert!(sorted
+                .iter()
+                .zip(&expected)
+                .all(|(a, b)| a.0.to_bits() == b.to_bits()));
+        }
+    }
+
+    #[test]
+    fn test_float_sort() {
+        let mut seed = 11;
+
+        for len in [0, 1, 2, 10, 100, 1000] {
+            let vec = floats(len, &mut seed);
+            let nans = vec.iter().filter(|x| x.is_nan()).count();
+            let mut expected: Vec<f64> = vec.iter().copied().filter(|x| !x.is_nan()).collect();
+            expected.sort_by(f64::total_cmp);
+
+            let mut sorted = vec.clone();
+            float_sort(&mut sorted, NanPolicy::First).unwrap();
+            assert!(sorted[..nans].iter().all(|x| x.is_nan()));
+            assert_eq!(bits(&sorted[nans..]), bits(&expected));
+
+            let mut sorted = vec.clone();
+            float_sort(&mut sorted, NanPolicy::Last).unwrap();
+            assert!(sorted[len - nans..].iter().all(|x| x.is_nan()));
+            assert_eq!(bits(&sorted[..len - nans]), bits(&expected));
+
+            let mut sorted = vec.clone();
+            match vec.iter().position(|x| x.is_nan()) {
+                Some(index) => {
+                    assert_eq!(
+                        float_sort(&mut sorted, NanPolicy::Error),
+                        Err(NanError { index })
+                    );
+                    assert_eq!(bits(&sorted), bits(&vec));
+                }
+                None => {
+                    float_sort(&mut sorted, NanPolicy::Error).unwrap();
+                    assert_eq!(bits(&sorted), bits(&expected));
+                }
+            }
+        }
+    }
+}
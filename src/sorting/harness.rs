@@ -0,0 +1,423 @@
+use super::*;
+
+/// 输入的长度：空数组、插入排序阈值附近的长度，以及较长的数组
+const LENS: [usize; 14] = [0, 1, 2, 3, 5, 8, 15, 16, 17, 32, 33, 100, 257, 1000];
+
+/// 超过并行阈值的长度，用来检查并行排序的拆分与合并
+const PAR_LENS: [usize; 2] = [5000, 20_000];
+
+/// 每个长度重复的轮数，随机输入每轮都不同
+const ROUNDS: usize = 3;
+
+/// 被排序的元素。
+///
+/// 只按 `key` 比较，`index` 记录元素在输入中的位置，用来检查排列和稳定性。
+#[derive(Debug, Clone, Copy)]
+struct Item {
+    key: u32,
+    index: usize,
+}
+
+impl PartialEq for Item {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Item {}
+
+impl PartialOrd for Item {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Item {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl RadixKey for Item {
+    fn key_len(&self) -> usize {
+        self.key.key_len()
+    }
+
+    fn key_byte(&self, level: usize) -> Option<u8> {
+        self.key.key_byte(level)
+    }
+}
+
+/// 长度为 `len` 的各种输入，返回模式的名称和键
+fn patterns(len: usize, seed: &mut u64) -> Vec<(&'static str, Vec<u32>)> {
+    // 键的范围不能太大，计数排序按范围分配空间
+    let random_keys: Vec<u32> = (0..len)
+        .map(|_| (random(seed) % 1_000_000) as u32)
+        .collect();
+    let few_keys: Vec<u32> = (0..len).map(|_| (random(seed) % 4) as u32).collect();
+
+    let sorted: Vec<u32> = (0..len as u32).collect();
+    let reversed: Vec<u32> = sorted.iter().rev().copied().collect();
+
+    // 先升后降
+    let organ_pipe: Vec<u32> = (0..len).map(|i| i.min(len - 1 - i) as u32).collect();
+
+    let mut nearly_sorted = sorted.clone();
+    for _ in 0..len / 20 {
+        let i = random(seed) as usize % len;
+        let j = random(seed) as usize % len;
+        nearly_sorted.swap(i, j);
+    }
+
+    vec![
+        ("random", random_keys),
+        ("few distinct", few_keys),
+        ("sorted", sorted),
+        ("reversed", reversed),
+        ("organ pipe", organ_pipe),
+        ("all equal", vec![7; len]),
+        ("sawtooth", (0..len).map(|i| (i % 10) as u32).collect()),
+        ("nearly sorted", nearly_sorted),
+    ]
+}
+
+/// 用 `sort` 排序所有输入，检查结果有序、是输入的一个排列，`stable` 时还检查稳定性
+fn check(name: &str, sort: impl Fn(&mut [Item]), stable: bool) {
+    check_lens(name, &LENS, |v, _| sort(v), |len| len, stable);
+}
+
+/// 依次对 `lens` 中各个长度的所有输入调用 `sort(vec, k)`，`k` 由 `prefix(len)` 给出。
+///
+/// 检查 `vec[..k]` 有序、不大于 `vec[k..]` 中的任何元素，且结果是输入的一个排列；
+/// `stable` 时还检查 `vec[..k]` 的稳定性。
+fn check_lens(
+    name: &str,
+    lens: &[usize],
+    sort: impl Fn(&mut [Item], usize),
+    prefix: impl Fn(usize) -> usize,
+    stable: bool,
+) {
+    let mut seed = 42;
+
+    for &len in lens {
+        let k = prefix(len);
+
+        for _ in 0..ROUNDS {
+            for (pattern, keys) in patterns(len, &mut seed) {
+                let mut vec: Vec<Item> = keys
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &key)| Item { key, index })
+                    .collect();
+                sort(&mut vec, k);
+
+                let context = format!("{}: {}, len {}, k {}", name, pattern, len, k);
+                let (head, tail) = vec.split_at(k);
+
+                assert!(
+                    head.windows(2).all(|w| w[0].key <= w[1].key),
+                    "{}: not sorted",
+                    context
+                );
+                assert!(
+                    head.last()
+                        .map_or(true, |last| tail.iter().all(|x| last.key <= x.key)),
+                    "{}: prefix is not the smallest",
+                    context
+                );
+
+                // 每个下标恰好出现一次，且键没有被改动
+                let mut seen = vec![false; len];
+                for item in &vec {
+                    assert!(
+                        item.index < len && !seen[item.index] && keys[item.index] == item.key,
+                        "{}: not a permutation",
+                        context
+                    );
+                    seen[item.index] = true;
+                }
+
+                if stable {
+                    assert!(
+                        head.windows(2)
+                            .all(|w| w[0].key < w[1].key || w[0].index < w[1].index),
+                        "{}: not stable",
+                        context
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// 并行排序除了 [`LENS`] 以外，还要检查超过并行阈值的长度
+fn check_parallel(name: &str, sort: impl Fn(&mut [Item]), stable: bool) {
+    check(name, &sort, stable);
+    check_lens(name, &PAR_LENS, |v, _| sort(v), |len| len, stable);
+}
+
+/// 部分排序取几个不同的 `k`：只排第一个、前三分之一和全部
+fn check_partial(name: &str, sort: impl Fn(&mut [Item], usize)) {
+    let prefixes: [fn(usize) -> usize; 4] = [|_| 0, |len| len.min(1), |len| len / 3, |len| len];
+    for prefix in prefixes {
+        check_lens(name, &LENS, &sort, prefix, false);
+    }
+}
+
+/// 按键比较，用于检查各个 `_by` 版本
+fn by_key(a: &Item, b: &Item) -> Ordering {
+    a.key.cmp(&b.key)
+}
+
+#[test]
+#[should_panic(expected = "not stable")]
+fn test_detects_unstable() {
+    check("heap_sort", heap_sort, true);
+}
+
+#[test]
+fn test_sorters() {
+    // 检查 `Sorter` 声称的稳定性
+    for sorter in comparison_sorters::<Item>() {
+        check(sorter.name(), |v| sorter.sort(v), sorter.is_stable());
+    }
+}
+
+#[test]
+fn test_bubble_sort() {
+    check("bubble_sort", bubble_sort, true);
+    check("bubble_sort_by", |v| bubble_sort_by(v, by_key), true);
+    check(
+        "bubble_sort_by_key",
+        |v| bubble_sort_by_key(v, |x| x.key),
+        true,
+    );
+}
+
+#[test]
+fn test_insertion_sort() {
+    check("insertion_sort", insertion_sort, true);
+    check("insertion_sort_by", |v| insertion_sort_by(v, by_key), true);
+    check(
+        "insertion_sort_by_key",
+        |v| insertion_sort_by_key(v, |x| x.key),
+        true,
+    );
+}
+
+#[test]
+fn test_branchless_insertion_sort() {
+    check("branchless_insertion_sort", branchless_insertion_sort, true);
+    check(
+        "branchless_insertion_sort_by",
+        |v| branchless_insertion_sort_by(v, by_key),
+        true,
+    );
+    check(
+        "branchless_insertion_sort_by_key",
+        |v| branchless_insertion_sort_by_key(v, |x| x.key),
+        true,
+    );
+}
+
+#[test]
+fn test_selection_sort() {
+    check("selection_sort", selection_sort, false);
+    check("selection_sort_by", |v| selection_sort_by(v, by_key), false);
+    check(
+        "selection_sort_by_key",
+        |v| selection_sort_by_key(v, |x| x.key),
+        false,
+    );
+}
+
+#[test]
+fn test_shell_sort() {
+    for gaps in [
+        GapSequence::Shell,
+        GapSequence::Knuth,
+        GapSequence::Sedgewick,
+        GapSequence::Ciura,
+        GapSequence::Tokuda,
+    ] {
+        check("shell_sort", |v| shell_sort(v, gaps), false);
+        check("shell_sort_by", |v| shell_sort_by(v, gaps, by_key), false);
+        check(
+            "shell_sort_by_key",
+            |v| shell_sort_by_key(v, gaps, |x| x.key),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_merge_sort() {
+    check(
+        "merge_sort",
+        |v| v.copy_from_slice(&merge_sort(v.to_vec())),
+        true,
+    );
+    check("merge_sort_slice", merge_sort_slice, true);
+    check(
+        "merge_sort_slice_by",
+        |v| merge_sort_slice_by(v, by_key),
+        true,
+    );
+    check(
+        "merge_sort_slice_by_key",
+        |v| merge_sort_slice_by_key(v, |x| x.key),
+        true,
+    );
+}
+
+#[test]
+fn test_merge_sort_in_place() {
+    check("merge_sort_in_place", merge_sort_in_place, true);
+    check(
+        "merge_sort_in_place_by",
+        |v| merge_sort_in_place_by(v, by_key),
+        true,
+    );
+    check(
+        "merge_sort_in_place_by_key",
+        |v| merge_sort_in_place_by_key(v, |x| x.key),
+        true,
+    );
+}
+
+#[test]
+fn test_iterative_sort() {
+    check("quick_sort_iterative", quick_sort_iterative, false);
+    check(
+        "quick_sort_iterative_by",
+        |v| quick_sort_iterative_by(v, by_key),
+        false,
+    );
+    check(
+        "quick_sort_iterative_by_key",
+        |v| quick_sort_iterative_by_key(v, |x| x.key),
+        false,
+    );
+    check("merge_sort_bottom_up", merge_sort_bottom_up, true);
+    check(
+        "merge_sort_bottom_up_by",
+        |v| merge_sort_bottom_up_by(v, by_key),
+        true,
+    );
+    check(
+        "merge_sort_bottom_up_by_key",
+        |v| merge_sort_bottom_up_by_key(v, |x| x.key),
+        true,
+    );
+}
+
+#[test]
+fn test_quick_sort() {
+    check("quick_sort", quick_sort, false);
+    check("quick_sort_by", |v| quick_sort_by(v, by_key), false);
+    check(
+        "quick_sort_by_key",
+        |v| quick_sort_by_key(v, |x| x.key),
+        false,
+    );
+}
+
+#[test]
+fn test_quick_sort_3way() {
+    check("quick_sort_3way", quick_sort_3way, false);
+    check(
+        "quick_sort_3way_by",
+        |v| quick_sort_3way_by(v, by_key),
+        false,
+    );
+    check(
+        "quick_sort_3way_by_key",
+        |v| quick_sort_3way_by_key(v, |x| x.key),
+        false,
+    );
+}
+
+#[test]
+fn test_heap_sort() {
+    check("heap_sort", heap_sort, false);
+    check("heap_sort_by", |v| heap_sort_by(v, by_key), false);
+    check(
+        "heap_sort_by_key",
+        |v| heap_sort_by_key(v, |x| x.key),
+        false,
+    );
+    check(
+        "BinaryHeap::into_sorted_vec",
+        |v| v.copy_from_slice(&BinaryHeap::from(v.to_vec()).into_sorted_vec()),
+        false,
+    );
+}
+
+#[test]
+fn test_intro_sort() {
+    check("intro_sort", intro_sort, false);
+    check("intro_sort_by", |v| intro_sort_by(v, by_key), false);
+    check(
+        "intro_sort_by_key",
+        |v| intro_sort_by_key(v, |x| x.key),
+        false,
+    );
+}
+
+#[test]
+fn test_tim_sort() {
+    check("tim_sort", tim_sort, true);
+    check("tim_sort_by", |v| tim_sort_by(v, by_key), true);
+    check("tim_sort_by_key", |v| tim_sort_by_key(v, |x| x.key), true);
+}
+
+#[test]
+fn test_radix_sort() {
+    check(
+        "counting_sort_by_key",
+        |v| counting_sort_by_key(v, |x| x.key as usize),
+        true,
+    );
+    check("radix_sort_lsd", radix_sort_lsd, true);
+    check(
+        "radix_sort_lsd_by_key",
+        |v| radix_sort_lsd_by_key(v, |x| x.key),
+        true,
+    );
+    check("radix_sort_msd", radix_sort_msd, false);
+}
+
+#[test]
+fn test_parallel_sort() {
+    check_parallel("par_merge_sort", |v| par_merge_sort(v, 4), true);
+    check_parallel(
+        "par_merge_sort_by",
+        |v| par_merge_sort_by(v, 4, by_key),
+        true,
+    );
+    check_parallel(
+        "par_merge_sort_by_key",
+        |v| par_merge_sort_by_key(v, 4, |x| x.key),
+        true,
+    );
+    check_parallel("par_quick_sort", |v| par_quick_sort(v, 4), false);
+    check_parallel(
+        "par_quick_sort_by",
+        |v| par_quick_sort_by(v, 4, by_key),
+        false,
+    );
+    check_parallel(
+        "par_quick_sort_by_key",
+        |v| par_quick_sort_by_key(v, 4, |x| x.key),
+        false,
+    );
+}
+
+#[test]
+fn test_partial_sort() {
+    check_partial("partial_sort", |v, k| partial_sort(v, k));
+    check_partial("partial_sort_by", |v, k| partial_sort_by(v, k, by_key));
+    check_partial("partial_sort_by_key", |v, k| {
+        partial_sort_by_key(v, k, |x| x.key)
+    });
+}
@@ -0,0 +1,376 @@
+use super::{less_at, Probe};
+use std::cmp::Ordering;
+
+/// # 堆排序
+///
+/// 先把数组原地建成最大堆，然后每轮把堆顶（当前最大元素）交换到末尾，
+/// 堆的范围缩小一位，再对新的堆顶做下沉，直到堆里只剩一个元素。
+///
+/// 原地排序、不递归，最坏时间复杂度也是 O(n log n)，但不稳定。
+///
+/// ```
+/// # use algorithm_study::sorting::heap_sort;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![1];
+/// let mut vec_2 = vec![2,1];
+/// let mut vec_3 = vec![3,5,2,4,1];
+///
+/// heap_sort(&mut vec_0);
+/// heap_sort(&mut vec_1);
+/// heap_sort(&mut vec_2);
+/// heap_sort(&mut vec_3);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1]);
+/// assert_eq!(vec_2, vec![1,2]);
+/// assert_eq!(vec_3, vec![1,2,3,4,5]);
+/// ```
+///
+pub fn heap_sort<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    heap_sort_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 堆排序（自定义比较）
+///
+/// 与 [`heap_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::heap_sort_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// heap_sort_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn heap_sort_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    heap_sort_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 堆排序（按键排序）
+///
+/// 与 [`heap_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::heap_sort_by_key;
+/// #
+/// let mut vec = vec![(3, 'a'), (1, 'b'), (2, 'c')];
+///
+/// heap_sort_by_key(&mut vec, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (2, 'c'), (3, 'a')]);
+/// ```
+///
+pub fn heap_sort_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    heap_sort_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+pub(crate) fn heap_sort_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    heapify(vec, is_less, probe);
+    sort_heap(vec, is_less, probe);
+}
+
+/// 把已经满足堆性质的数组排成升序。
+///
+/// 每轮把堆顶换到末尾，堆的范围缩小一位，再对新的堆顶下沉。
+pub(crate) fn sort_heap<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    for end in (1..vec.len()).rev() {
+        vec.swap(0, end);
+        probe.swap(0, end);
+        sift_down(&mut vec[..end], 0, is_less, probe);
+    }
+}
+
+/// 把整个数组原地调整成最大堆。
+///
+/// 叶子节点本身就是堆，只需从最后一个非叶子节点开始，从后往前依次下沉。
+pub(crate) fn heapify<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    for node in (0..vec.len() / 2).rev() {
+        sift_down(vec, node, is_less, probe);
+    }
+}
+
+/// 上浮：`node` 比父节点大时与之交换，直到满足堆的性质。
+pub(crate) fn sift_up<T, F, P>(vec: &mut [T], mut node: usize, is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    while node > 0 {
+        let parent = (node - 1) / 2;
+
+        if !less_at(vec, parent, node, is_less, probe) {
+            break;
+        }
+
+        vec.swap(parent, node);
+        probe.swap(parent, node);
+        node = parent;
+    }
+}
+
+/// 下沉：`node` 比较大的子节点小时与之交换，直到满足堆的性质。
+pub(crate) fn sift_down<T, F, P>(vec: &mut [T], mut node: usize, is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    loop {
+        let mut child = 2 * node + 1;
+
+        if child >= vec.len() {
+            break;
+        }
+
+        // 选出较大的子节点
+        if child + 1 < vec.len() && less_at(vec, child, child + 1, is_less, probe) {
+            child += 1;
+        }
+
+        if !less_at(vec, node, child, is_less, probe) {
+            break;
+        }
+
+        vec.swap(node, child);
+        probe.swap(node, child);
+        node = child;
+    }
+}
+
+/// # 二叉堆
+///
+/// 用数组存储的最大堆，下标 `i` 的子节点是 `2i + 1` 和 `2i + 2`。
+/// 堆顶始终是最大的元素，可以作为优先队列使用。
+///
+/// ```
+/// use algorithm_study::sorting::BinaryHeap;
+///
+/// let mut heap = BinaryHeap::new();
+///
+/// heap.push(3);
+/// heap.push(5);
+/// heap.push(1);
+///
+/// assert_eq!(heap.peek(), Some(&5));
+/// assert_eq!(heap.pop(), Some(5));
+/// assert_eq!(heap.pop(), Some(3));
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.pop(), None);
+/// ```
+///
+pub struct BinaryHeap<T> {
+    data: Vec<T>,
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    /// 创建空的二叉堆。
+    ///
+    /// # Examples
+    /// ---
+    /// ```
+    /// # use algorithm_study::sorting::BinaryHeap;
+    /// #
+    /// let heap: BinaryHeap<i32> = BinaryHeap::new();
+    ///
+    /// assert!(heap.is_empty());
+    /// ```
+    ///
+    pub fn new() -> BinaryHeap<T> {
+        BinaryHeap { data: Vec::new() }
+    }
+
+    /// 放入一个元素，放在末尾后上浮。
+    ///
+    /// # Examples
+    /// ---
+    /// ```
+    /// # use algorithm_study::sorting::BinaryHeap;
+    /// #
+    /// let mut heap = BinaryHeap::new();
+    /// heap.push(1);
+    /// heap.push(2);
+    ///
+    /// assert_eq!(heap.len(), 2);
+    /// assert_eq!(heap.peek(), Some(&2));
+    /// ```
+    ///
+    pub fn push(&mut self, item: T) {
+        self.data.push(item);
+        let last = self.data.len() - 1;
+        sift_up(&mut self.data, last, &mut |a: &T, b: &T| a < b, &());
+    }
+
+    /// 取出最大的元素，把末尾元素换到堆顶后下沉。
+    ///
+    /// # Examples
+    /// ---
+    /// ```
+    /// # use algorithm_study::sorting::BinaryHeap;
+    /// #
+    /// let mut heap = BinaryHeap::from(vec![1, 3, 2]);
+    ///
+    /// assert_eq!(heap.pop(), Some(3));
+    /// assert_eq!(heap.len(), 2);
+    /// ```
+    ///
+    pub fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let top = self.data.swap_remove(0);
+        sift_down(&mut self.data, 0, &mut |a: &T, b: &T| a < b, &());
+        Some(top)
+    }
+
+    /// 查看最大的元素，不取出。
+    ///
+    /// # Examples
+    /// ---
+    /// ```
+    /// # use algorithm_study::sorting::BinaryHeap;
+    /// #
+    /// let heap = BinaryHeap::from(vec![1, 3, 2]);
+    ///
+    /// assert_eq!(heap.peek(), Some(&3));
+    /// ```
+    ///
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// 消耗整个堆，返回升序排列的数组，即原地堆排序的后半部分。
+    ///
+    /// # Examples
+    /// ---
+    /// ```
+    /// # use algorithm_study::sorting::BinaryHeap;
+    /// #
+    /// let heap = BinaryHeap::from(vec![3, 5, 2, 4, 1]);
+    ///
+    /// assert_eq!(heap.into_sorted_vec(), vec![1, 2, 3, 4, 5]);
+    /// ```
+    ///
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        sort_heap(&mut self.data, &mut |a: &T, b: &T| a < b, &());
+        self.data
+    }
+}
+
+impl<T> BinaryHeap<T> {
+    /// 堆中元素的个数。
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// 堆是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// 消耗整个堆，按内部存储顺序返回数组。
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+impl<T: Ord> Default for BinaryHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for BinaryHeap<T> {
+    /// 用 O(n) 的建堆方式，把数组原地转换成二叉堆。
+    fn from(mut vec: Vec<T>) -> Self {
+        heapify(&mut vec, &mut |a: &T, b: &T| a < b, &());
+        BinaryHeap { data: vec }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn is_heap<T: Ord>(heap: &BinaryHeap<T>) -> bool {
+        (1..heap.data.len()).all(|i| heap.data[(i - 1) / 2] >= heap.data[i])
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let heap = BinaryHeap::from(vec![4, 8, 1, 9, 3, 3, 7, 0, 2]);
+        assert!(is_heap(&heap));
+        assert_eq!(9, heap.len());
+        assert_eq!(Some(&9), heap.peek());
+
+        let heap = BinaryHeap::<i32>::from(vec![]);
+        assert!(heap.is_empty());
+        assert_eq!(None, heap.peek());
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = BinaryHeap::new();
+
+        for i in [5, 1, 8, 3, 9, 2, 8] {
+            heap.push(i);
+            assert!(is_heap(&heap));
+        }
+
+        let mut popped = Vec::new();
+        while let Some(i) = heap.pop() {
+            assert!(is_heap(&heap));
+            popped.push(i);
+        }
+        assert_eq!(vec![9, 8, 8, 5, 3, 2, 1], popped);
+        assert_eq!(None, heap.pop());
+    }
+
+    #[test]
+    fn test_into_sorted_vec() {
+        let heap = BinaryHeap::from(vec!["pear", "apple", "fig", "kiwi"]);
+        assert_eq!(vec!["apple", "fig", "kiwi", "pear"], heap.into_sorted_vec());
+    }
+
+    #[test]
+    fn test_heap_sort() {
+        let mut vec: Vec<String> = ["d", "a", "c", "b", "a"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        heap_sort(&mut vec);
+        assert_eq!(vec!["a", "a", "b", "c", "d"], vec);
+
+        let mut vec: Vec<i32> = (0..100).rev().collect();
+        heap_sort(&mut vec);
+        assert_eq!((0..100).collect::<Vec<_>>(), vec);
+    }
+}
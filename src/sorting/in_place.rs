@@ -0,0 +1,234 @@
+use super::{less_at, small, Probe, INSERTION_LEN};
+use std::cmp::Ordering;
+
+/// # 原地归并排序
+///
+/// 稳定排序，不分配任何辅助空间：先把数组分成长度为 16 的块，各自用
+/// [`branchless_insertion_sort`](super::branchless_insertion_sort) 排序，
+/// 再自底向上两两合并。
+///
+/// 合并 `A B` 两段时不借助缓冲区，而是用旋转交换元素：取较长一段的中点 `x`，
+/// 在另一段中二分查找 `x` 的位置，把两段各切成两半 `A1 A2 B1 B2`，
+/// 旋转中间的 `A2 B1` 得到 `A1 B1 A2 B2`，再分别合并 `A1 B1` 与 `A2 B2`。
+/// 只对较短的一侧递归，另一侧在循环中继续，栈的深度不超过 `log2(n)`。
+///
+/// 代价是元素的移动次数更多，时间复杂度 O(n log² n)，
+/// 比 [`merge_sort_slice`](super::merge_sort_slice) 慢，适合内存受限的场合。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_in_place;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![1];
+/// let mut vec_2 = vec![3,5,2,4,1];
+/// let mut vec_3: Vec<i32> = (0..10_000).rev().collect();
+///
+/// merge_sort_in_place(&mut vec_0);
+/// merge_sort_in_place(&mut vec_1);
+/// merge_sort_in_place(&mut vec_2);
+/// merge_sort_in_place(&mut vec_3);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1]);
+/// assert_eq!(vec_2, vec![1,2,3,4,5]);
+/// assert!(vec_3.iter().copied().eq(0..10_000));
+/// ```
+///
+pub fn merge_sort_in_place<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    merge_sort_in_place_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 原地归并排序（自定义比较）
+///
+/// 与 [`merge_sort_in_place`] 相同，但使用 `compare` 决定元素顺序。排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_in_place_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// merge_sort_in_place_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn merge_sort_in_place_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_in_place_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 原地归并排序（按键排序）
+///
+/// 与 [`merge_sort_in_place`] 相同，但按 `f` 提取的键排序。排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_in_place_by_key;
+/// #
+/// let mut vec = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+///
+/// merge_sort_in_place_by_key(&mut vec, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+/// ```
+///
+pub fn merge_sort_in_place_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    merge_sort_in_place_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+pub(super) fn merge_sort_in_place_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let len = vec.len();
+
+    // 短块直接用插入排序
+    let mut start = 0;
+    while start < len {
+        let end = len.min(start + INSERTION_LEN);
+        probe.shift(start);
+        small::branchless_insertion_sort_impl(&mut vec[start..end], is_less, probe);
+        probe.unshift(start);
+        start = end;
+    }
+
+    let mut width = INSERTION_LEN;
+    while width < len {
+        let mut start = 0;
+        while start + width < len {
+            let end = len.min(start + 2 * width);
+            probe.shift(start);
+            merge_in_place(&mut vec[start..end], width, is_less, probe);
+            probe.unshift(start);
+            start = end;
+        }
+        width *= 2;
+    }
+}
+
+/// 不借助辅助空间合并 `vec[..mid]` 与 `vec[mid..]` 两段有序区间，排序是稳定的。
+fn merge_in_place<T, F, P>(vec: &mut [T], mid: usize, is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let len = vec.len();
+
+    if mid == 0 || mid >= len {
+        return;
+    }
+
+    probe.merge_range(0, mid, len);
+    rotate_merge(vec, 0, mid, len, is_less, probe);
+
+    /// 合并 `vec[start..mid]` 与 `vec[mid..end]`，下标都相对于整个 `vec`
+    fn rotate_merge<T, F, P>(
+        vec: &mut [T],
+        mut start: usize,
+        mut mid: usize,
+        mut end: usize,
+        is_less: &mut F,
+        probe: &P,
+    ) where
+        F: FnMut(&T, &T) -> bool,
+        P: Probe,
+    {
+        // 左段末尾不大于右段开头时已经有序，合并结束
+        while start < mid && mid < end && less_at(vec, mid, mid - 1, is_less, probe) {
+            if end - start == 2 {
+                vec.swap(start, mid);
+                probe.swap(start, mid);
+                return;
+            }
+
+            // 较长一段的中点作为 x，在另一段中找到 x 的位置；
+            // 右段中等于 x 的元素留在 x 之后，左段中等于 x 的元素留在 x 之前，保证稳定
+            let (cut_left, cut_right) = if mid - start >= end - mid {
+                let cut_left = start + (mid - start) / 2;
+                let cut_right = search(vec, mid, end, |v, i| {
+                    less_at(v, i, cut_left, is_less, probe)
+                });
+                (cut_left, cut_right)
+            } else {
+                let cut_right = mid + (end - mid) / 2;
+                let cut_left = search(vec, start, mid, |v, i| {
+                    !less_at(v, cut_right, i, is_less, probe)
+                });
+                (cut_left, cut_right)
+            };
+
+            // A1 A2 B1 B2 -> A1 B1 A2 B2
+            vec[cut_left..cut_right].rotate_left(mid - cut_left);
+            probe.moves(cut_right - cut_left);
+            let new_mid = cut_left + (cut_right - mid);
+
+            // 对较短的一侧递归，较长的一侧继续循环
+            probe.enter();
+            if new_mid - start < end - new_mid {
+                rotate_merge(vec, start, cut_left, new_mid, is_less, probe);
+                (start, mid) = (new_mid, cut_right);
+            } else {
+                rotate_merge(vec, new_mid, cut_right, end, is_less, probe);
+                (mid, end) = (cut_left, new_mid);
+            }
+            probe.leave();
+        }
+    }
+
+    /// `vec[lo..hi]` 中第一个使 `pred` 为假的位置，`pred` 在区间内先真后假
+    fn search<T, G>(vec: &[T], mut lo: usize, mut hi: usize, mut pred: G) -> usize
+    where
+        G: FnMut(&[T], usize) -> bool,
+    {
+        while lo < hi {
+            let m = lo + (hi - lo) / 2;
+            if pred(vec, m) {
+                lo = m + 1;
+            } else {
+                hi = m;
+            }
+        }
+        lo
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::random;
+
+    #[test]
+    fn test_merge() {
+        // 所有长度与分割点，键的取值很少，有大量相等的元素
+        let mut seed = 1;
+        for len in 0..40 {
+            for mid in 0..=len {
+                for _ in 0..4 {
+                    let mut vec: Vec<(u64, usize)> =
+                        (0..len).map(|i| (random(&mut seed) % 4, i)).collect();
+                    vec[..mid].sort();
+                    vec[mid..].sort();
+
+                    let mut expected = vec.clone();
+                    expected.sort_by_key(|x| x.0);
+
+                    merge_in_place(&mut vec, mid, &mut |a, b| a.0 < b.0, &());
+                    assert_eq!(vec, expected, "len = {}, mid = {}", len, mid);
+                }
+            }
+        }
+    }
+}
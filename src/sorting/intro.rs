@@ -0,0 +1,169 @@
+use super::{depth_limit, quick_sort_with_idx, Probe, SmallSort};
+use std::cmp::Ordering;
+
+/// # 内省排序
+///
+/// 以快速排序为主体，结合另外两种排序弥补它的短板：
+///
+/// - 分区点取三数中位数，长区间取九数中位数；
+/// - 分区层数超过 `2·log2(n)` 时改用堆排序，最坏时间复杂度为 O(n log n)；
+/// - 短区间改用插入排序，减少递归和分区的开销。
+///
+/// 分区和堆排序的部分与 [`quick_sort`](super::quick_sort) 相同，区别在短区间：
+/// `quick_sort` 用 [`sort_network`](super::sort_network)，这里用插入排序，
+/// 可以对比两者在短区间上的开销。
+///
+/// 原地排序，不稳定。
+///
+/// ```
+/// # use algorithm_study::sorting::intro_sort;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![1];
+/// let mut vec_2 = vec![3,5,2,4,1];
+/// let mut vec_3: Vec<i32> = (0..1000).rev().collect();
+///
+/// intro_sort(&mut vec_0);
+/// intro_sort(&mut vec_1);
+/// intro_sort(&mut vec_2);
+/// intro_sort(&mut vec_3);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1]);
+/// assert_eq!(vec_2, vec![1,2,3,4,5]);
+/// assert_eq!(vec_3, (0..1000).collect::<Vec<_>>());
+/// ```
+///
+pub fn intro_sort<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    intro_sort_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 内省排序（自定义比较）
+///
+/// 与 [`intro_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::intro_sort_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// intro_sort_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn intro_sort_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    intro_sort_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 内省排序（按键排序）
+///
+/// 与 [`intro_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::intro_sort_by_key;
+/// #
+/// let mut vec = vec![(3, 'a'), (1, 'b'), (2, 'c')];
+///
+/// intro_sort_by_key(&mut vec, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (2, 'c'), (3, 'a')]);
+/// ```
+///
+pub fn intro_sort_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    intro_sort_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+pub(super) fn intro_sort_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let limit = depth_limit(vec.len());
+    quick_sort_with_idx(
+        vec,
+        0,
+        vec.len(),
+        limit,
+        SmallSort::Insertion,
+        is_less,
+        probe,
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::quick_sort;
+
+    /// 三数中位数的“杀手”序列，会让朴素的三数取中快速排序退化
+    fn median_of_three_killer(len: usize) -> Vec<usize> {
+        let k = len / 2;
+        let mut vec = vec![0; len];
+        for i in 1..=k {
+            if i % 2 == 1 {
+                vec[i - 1] = i;
+                vec[i] = k + i;
+            }
+            vec[k + i - 1] = 2 * i;
+        }
+        vec
+    }
+
+    #[test]
+    fn test_patterns() {
+        let len = 10_000;
+        let patterns: Vec<Vec<usize>> = vec![
+            (0..len).collect(),
+            (0..len).rev().collect(),
+            vec![7; len],
+            (0..len).map(|i| i % 3).collect(),
+            (0..len)
+                .map(|i| if i < len / 2 { i } else { len - i })
+                .collect(),
+            median_of_three_killer(len),
+        ];
+
+        for vec in patterns {
+            let mut expected = vec.clone();
+            expected.sort();
+
+            let mut sorted = vec.clone();
+            intro_sort(&mut sorted);
+            assert_eq!(expected, sorted);
+
+            let mut sorted = vec.clone();
+            quick_sort(&mut sorted);
+            assert_eq!(expected, sorted);
+        }
+    }
+
+    #[test]
+    fn test_comparisons_on_sorted_input() {
+        // 有序输入不应退化成 O(n²)
+        let len = 10_000;
+        let mut count = 0;
+        let mut vec: Vec<usize> = (0..len).collect();
+
+        intro_sort_by(&mut vec, |a, b| {
+            count += 1;
+            a.cmp(b)
+        });
+
+        assert!(count < 4 * len * (usize::BITS - len.leading_zeros()) as usize);
+    }
+}
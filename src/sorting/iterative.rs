@@ -0,0 +1,278 @@
+use super::{depth_limit, heap, insertion_sort_impl, merge, partition, Probe, INSERTION_LEN};
+use std::cmp::Ordering;
+
+/// # 快速排序（非递归）
+///
+/// 与 [`quick_sort`](super::quick_sort) 使用同样的分区方式，但不递归：
+/// 用一个显式的栈保存待排序的区间，每次分区后把较长的一侧压栈，继续处理较短的一侧。
+/// 栈中相邻两个区间的长度至少减半，栈的深度不超过 `log2(n)`，在栈很小的线程里也能排序。
+///
+/// 分区层数超过 `2·log2(n)` 时改用堆排序，最坏也是 O(n log n)。
+/// 与 `quick_sort` 不同，长度不超过 16 的区间不再分区，改用插入排序。
+/// 原地排序，不稳定。
+///
+/// ```
+/// # use algorithm_study::sorting::quick_sort_iterative;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![1];
+/// let mut vec_2 = vec![3,5,2,4,1];
+/// let mut vec_3: Vec<i32> = (0..10_000).collect();
+///
+/// quick_sort_iterative(&mut vec_0);
+/// quick_sort_iterative(&mut vec_1);
+/// quick_sort_iterative(&mut vec_2);
+/// quick_sort_iterative(&mut vec_3);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1]);
+/// assert_eq!(vec_2, vec![1,2,3,4,5]);
+/// assert!(vec_3.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+///
+pub fn quick_sort_iterative<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    quick_sort_iterative_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 快速排序（非递归，自定义比较）
+///
+/// 与 [`quick_sort_iterative`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::quick_sort_iterative_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// quick_sort_iterative_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn quick_sort_iterative_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quick_sort_iterative_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 快速排序（非递归，按键排序）
+///
+/// 与 [`quick_sort_iterative`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::quick_sort_iterative_by_key;
+/// #
+/// let mut vec = vec![-3i32, 1, -2];
+///
+/// quick_sort_iterative_by_key(&mut vec, |x| x.abs());
+///
+/// assert_eq!(vec, vec![1, -2, -3]);
+/// ```
+///
+pub fn quick_sort_iterative_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    quick_sort_iterative_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+pub(super) fn quick_sort_iterative_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let len = vec.len();
+
+    // 栈中每个区间的长度不超过前一个的一半，容量足够时不会再分配
+    let mut stack: Vec<(usize, usize, u32)> = Vec::with_capacity(usize::BITS as usize);
+    let (mut start, mut end, mut limit) = (0, len, depth_limit(len));
+
+    loop {
+        if end - start <= INSERTION_LEN {
+            probe.shift(start);
+            insertion_sort_impl(&mut vec[start..end], is_less, probe);
+            probe.unshift(start);
+        } else if limit == 0 {
+            probe.shift(start);
+            heap::heap_sort_impl(&mut vec[start..end], is_less, probe);
+            probe.unshift(start);
+        } else {
+            limit -= 1;
+            let mid = partition(vec, start, end, is_less, probe);
+
+            // 较长的一侧压栈，接着处理较短的一侧
+            if mid - start < end - mid {
+                stack.push((mid + 1, end, limit));
+                end = mid;
+            } else {
+                stack.push((start, mid, limit));
+                start = mid + 1;
+            }
+            continue;
+        }
+
+        match stack.pop() {
+            Some(range) => (start, end, limit) = range,
+            None => break,
+        }
+    }
+}
+
+/// # 归并排序（自底向上）
+///
+/// 不递归的归并排序：先把相邻的单个元素两两合并成长度为 2 的有序段，
+/// 再把相邻的有序段两两合并，段长每轮翻倍，直到覆盖整个数组。
+///
+/// 只用循环，除了合并用的辅助空间（长度为切片的一半）以外只占用 O(1) 的空间。
+/// 合并与 [`merge_sort_slice`](super::merge_sort_slice) 相同，排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_bottom_up;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![1];
+/// let mut vec_2 = vec![3,5,2,4,1];
+/// let mut strs = vec!["b".to_string(), "c".to_string(), "a".to_string()];
+///
+/// merge_sort_bottom_up(&mut vec_0);
+/// merge_sort_bottom_up(&mut vec_1);
+/// merge_sort_bottom_up(&mut vec_2);
+/// merge_sort_bottom_up(&mut strs);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1]);
+/// assert_eq!(vec_2, vec![1,2,3,4,5]);
+/// assert_eq!(strs, vec!["a", "b", "c"]);
+/// ```
+///
+pub fn merge_sort_bottom_up<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    merge_sort_bottom_up_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 归并排序（自底向上，自定义比较）
+///
+/// 与 [`merge_sort_bottom_up`] 相同，但使用 `compare` 决定元素顺序。排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_bottom_up_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// merge_sort_bottom_up_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn merge_sort_bottom_up_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_bottom_up_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 归并排序（自底向上，按键排序）
+///
+/// 与 [`merge_sort_bottom_up`] 相同，但按 `f` 提取的键排序。排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_bottom_up_by_key;
+/// #
+/// let mut vec = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+///
+/// merge_sort_bottom_up_by_key(&mut vec, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+/// ```
+///
+pub fn merge_sort_bottom_up_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    merge_sort_bottom_up_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+pub(super) fn merge_sort_bottom_up_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let len = vec.len();
+
+    // 合并时较短的一段不超过整个数组的一半
+    let mut buf = Vec::with_capacity(len / 2);
+    probe.alloc(len / 2);
+
+    let mut width = 1;
+    while width < len {
+        // 合并 vec[start..start + width] 与紧随其后的一段，最后一段可能较短或不存在
+        let mut start = 0;
+        while start + width < len {
+            let end = len.min(start + 2 * width);
+            probe.shift(start);
+            merge(&mut vec[start..end], width, &mut buf, is_less, probe);
+            probe.unshift(start);
+            start = end;
+        }
+        width *= 2;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::{merge_sort_slice, quick_sort};
+    use std::thread;
+
+    /// 测试线程的栈大小，按元素个数递归时远远不够
+    const SMALL_STACK: usize = 64 * 1024;
+
+    /// 在栈很小的线程里排序一百万个元素
+    fn sort_on_small_stack(sort: fn(&mut [u32])) {
+        thread::Builder::new()
+            .stack_size(SMALL_STACK)
+            .spawn(move || {
+                let n = 1_000_000;
+
+                let mut vec: Vec<u32> = (0..n).collect();
+                sort(&mut vec);
+                assert!(vec.iter().copied().eq(0..n));
+
+                let mut vec: Vec<u32> = (0..n).rev().collect();
+                sort(&mut vec);
+                assert!(vec.iter().copied().eq(0..n));
+
+                let mut vec: Vec<u32> = vec![7; n as usize];
+                sort(&mut vec);
+                assert!(vec.iter().all(|&x| x == 7));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_small_stack() {
+        sort_on_small_stack(quick_sort_iterative);
+        sort_on_small_stack(merge_sort_bottom_up);
+
+        // 递归版本只对较短的一侧递归，深度也是 O(log n)
+        sort_on_small_stack(quick_sort);
+        sort_on_small_stack(merge_sort_slice);
+    }
+}
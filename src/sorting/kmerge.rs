@@ -0,0 +1,304 @@
+use super::heap;
+use std::cmp::Ordering;
+use std::iter::FusedIterator;
+use std::mem;
+
+/// # 多路归并
+///
+/// 把任意多个已经升序排列的迭代器合并成一个升序的迭代器。
+///
+/// 用堆存放每个迭代器的当前元素，每次取出堆顶，再从同一个迭代器补上下一个元素，
+/// 元素是按需读取的，不会一次性读入所有数据，因此也可以合并无穷的迭代器。
+/// 共 `k` 个迭代器时，每个元素的时间复杂度为 O(log k)，占用 O(k) 的空间。
+///
+/// 合并是稳定的：相等的元素中，来自更靠前的迭代器的先输出。
+/// 调用 [`dedup`](KMerge::dedup) 可以去掉重复的元素。
+///
+/// ```
+/// # use algorithm_study::sorting::kmerge;
+/// #
+/// let merged: Vec<i32> = kmerge(vec![vec![1,4,7], vec![2,5,8], vec![], vec![3,6,9]]).collect();
+///
+/// assert_eq!(merged, vec![1,2,3,4,5,6,7,8,9]);
+///
+/// // 无穷的迭代器
+/// let evens = (0..).step_by(2);
+/// let odds = (1..).step_by(2);
+///
+/// assert_eq!(kmerge([evens, odds]).take(5).collect::<Vec<_>>(), vec![0,1,2,3,4]);
+/// ```
+///
+pub fn kmerge<I>(iters: I) -> KMerge<Source<I>, impl FnMut(&Item<I>, &Item<I>) -> Ordering>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    Item<I>: Ord,
+{
+    KMerge::new(iters, |a: &Item<I>, b: &Item<I>| a.cmp(b))
+}
+
+/// # 多路归并（自定义比较）
+///
+/// 与 [`kmerge`] 相同，但使用 `compare` 决定元素顺序，
+/// 每个迭代器都需要按 `compare` 升序排列。
+///
+/// ```
+/// # use algorithm_study::sorting::kmerge_by;
+/// #
+/// let merged: Vec<i32> = kmerge_by(vec![vec![7,4,1], vec![8,5,2]], |a, b| b.cmp(a)).collect();
+///
+/// assert_eq!(merged, vec![8,7,5,4,2,1]);
+/// ```
+///
+pub fn kmerge_by<I, F>(iters: I, compare: F) -> KMerge<Source<I>, F>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    F: FnMut(&Item<I>, &Item<I>) -> Ordering,
+{
+    KMerge::new(iters, compare)
+}
+
+/// # 多路归并（按键排序）
+///
+/// 与 [`kmerge`] 相同，但按 `f` 提取的键排序，每个迭代器都需要按键升序排列。
+///
+/// ```
+/// # use algorithm_study::sorting::kmerge_by_key;
+/// #
+/// let a = vec![(1, 'a'), (3, 'a')];
+/// let b = vec![(1, 'b'), (2, 'b')];
+///
+/// assert_eq!(
+///     kmerge_by_key([a, b], |x| x.0).collect::<Vec<_>>(),
+///     vec![(1, 'a'), (1, 'b'), (2, 'b'), (3, 'a')]
+/// );
+/// ```
+///
+pub fn kmerge_by_key<I, K, F>(
+    iters: I,
+    mut f: F,
+) -> KMerge<Source<I>, impl FnMut(&Item<I>, &Item<I>) -> Ordering>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    K: Ord,
+    F: FnMut(&Item<I>) -> K,
+{
+    KMerge::new(iters, move |a: &Item<I>, b: &Item<I>| f(a).cmp(&f(b)))
+}
+
+/// 被合并的迭代器
+type Source<I> = <<I as IntoIterator>::Item as IntoIterator>::IntoIter;
+
+/// 被合并的迭代器中的元素类型
+type Item<I> = <<I as IntoIterator>::Item as IntoIterator>::Item;
+
+/// # 多路归并迭代器
+///
+/// 由 [`kmerge`]、[`kmerge_by`] 和 [`kmerge_by_key`] 创建。
+pub struct KMerge<I: Iterator, F> {
+    /// 以 `compare` 排序的堆，堆顶是下一个要输出的元素
+    heap: Vec<Head<I>>,
+    compare: F,
+    dedup: bool,
+}
+
+/// 一个迭代器的当前元素，以及它在参数中的序号
+struct Head<I: Iterator> {
+    item: I::Item,
+    index: usize,
+    iter: I,
+}
+
+impl<I, F> KMerge<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    fn new<S>(iters: S, mut compare: F) -> Self
+    where
+        S: IntoIterator,
+        S::Item: IntoIterator<IntoIter = I>,
+    {
+        let mut heap: Vec<Head<I>> = iters
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, iter)| {
+                let mut iter = iter.into_iter();
+                iter.next().map(|item| Head { item, index, iter })
+            })
+            .collect();
+        heap::heapify(&mut heap, &mut head_less(&mut compare), &());
+
+        KMerge {
+            heap,
+            compare,
+            dedup: false,
+        }
+    }
+
+    /// 去掉重复的元素：连续相等（`compare` 返回 `Equal`）的元素只输出第一个，
+    /// 无论它们来自同一个迭代器还是不同的迭代器。
+    ///
+    /// # Examples
+    /// ---
+    /// ```
+    /// # use algorithm_study::sorting::kmerge;
+    /// #
+    /// let merged: Vec<i32> = kmerge(vec![vec![1,2,2,3], vec![2,3,4]]).dedup().collect();
+    ///
+    /// assert_eq!(merged, vec![1,2,3,4]);
+    /// ```
+    ///
+    pub fn dedup(mut self) -> Self {
+        self.dedup = true;
+        self
+    }
+
+    /// 取出堆顶的元素，从同一个迭代器补上下一个元素，迭代器耗尽就把它移出堆
+    fn pop(&mut self) -> I::Item {
+        let head = &mut self.heap[0];
+        let item = match head.iter.next() {
+            Some(next) => mem::replace(&mut head.item, next),
+            None => self.heap.swap_remove(0).item,
+        };
+        heap::sift_down(&mut self.heap, 0, &mut head_less(&mut self.compare), &());
+        item
+    }
+}
+
+impl<I, F> Iterator for KMerge<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if self.heap.is_empty() {
+            return None;
+        }
+
+        let item = self.pop();
+        if self.dedup {
+            while !self.heap.is_empty()
+                && (self.compare)(&self.heap[0].item, &item) == Ordering::Equal
+            {
+                self.pop();
+            }
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self
+            .heap
+            .iter()
+            .map(|head| {
+                let (lower, upper) = head.iter.size_hint();
+                (
+                    lower.saturating_add(1),
+                    upper.and_then(|n| n.checked_add(1)),
+                )
+            })
+            .fold((0usize, Some(0usize)), |(lower, upper), (l, u)| {
+                (
+                    lower.saturating_add(l),
+                    upper.zip(u).and_then(|(a, b)| a.checked_add(b)),
+                )
+            });
+
+        // 去重时只能确定还有没有元素
+        if self.dedup {
+            (lower.min(1), upper)
+        } else {
+            (lower, upper)
+        }
+    }
+}
+
+impl<I, F> FusedIterator for KMerge<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+}
+
+/// 堆顶是“最大”的元素，因此反过来比较：内容更小的，或内容相等但序号更小的更“大”
+fn head_less<I, F>(compare: &mut F) -> impl FnMut(&Head<I>, &Head<I>) -> bool + '_
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    move |a, b| match compare(&b.item, &a.item) {
+        Ordering::Equal => b.index < a.index,
+        ord => ord == Ordering::Less,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::random;
+
+    /// 生成 `k` 个各自升序的数组，元素为 `(值, 数组序号)`
+    fn shards(k: usize, seed: &mut u64) -> Vec<Vec<(u64, usize)>> {
+        (0..k)
+            .map(|i| {
+                let len = (random(seed) % 50) as usize;
+                let mut shard: Vec<_> = (0..len).map(|_| (random(seed) % 20, i)).collect();
+                shard.sort();
+                shard
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_kmerge() {
+        let mut seed = 1;
+
+        for k in 0..20 {
+            let shards = shards(k, &mut seed);
+            let mut expected: Vec<_> = shards.iter().flatten().copied().collect();
+            expected.sort();
+
+            let merged = kmerge(shards.clone());
+            assert_eq!(merged.size_hint(), (expected.len(), Some(expected.len())));
+            assert_eq!(merged.collect::<Vec<_>>(), expected);
+
+            // 只按值比较，相等的元素保持数组的先后顺序
+            let merged: Vec<_> = kmerge_by_key(shards, |x| x.0).collect();
+            assert_eq!(merged, expected);
+        }
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut seed = 2;
+
+        for k in 0..20 {
+            let shards = shards(k, &mut seed);
+            let mut expected: Vec<_> = shards.iter().flatten().copied().collect();
+            expected.sort();
+            expected.dedup_by_key(|x| x.0);
+
+            let merged: Vec<_> = kmerge_by_key(shards, |x| x.0).dedup().collect();
+            assert_eq!(merged, expected);
+        }
+    }
+
+    #[test]
+    fn test_lazy() {
+        let mut pulled = 0;
+        let counted = (0..).inspect(|_| pulled += 1);
+
+        let mut merged = kmerge_by([counted], i32::cmp);
+        assert_eq!(merged.next(), Some(0));
+        assert_eq!(merged.next(), Some(1));
+        drop(merged);
+
+        // 初始化时读入一个，之后每输出一个再补一个
+        assert_eq!(pulled, 3);
+    }
+}
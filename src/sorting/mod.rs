@@ -1,3 +1,83 @@
+use probe::Probe;
+use std::cmp::Ordering;
+use std::ptr;
+
+mod external;
+mod float;
+/// 所有排序算法的正确性与稳定性检查：随机输入和几种容易触发最坏情况的输入
+#[cfg(test)]
+mod harness;
+mod heap;
+mod in_place;
+mod intro;
+mod iterative;
+mod kmerge;
+mod parallel;
+mod permutation;
+mod probe;
+mod radix;
+mod select;
+mod shell;
+mod small;
+mod sorter;
+mod stats;
+mod three_way;
+mod tim;
+mod trace;
+
+pub use external::ExternalSort;
+pub use float::{float_sort, Float, NanError, NanPolicy, TotalOrd};
+pub use heap::{heap_sort, heap_sort_by, heap_sort_by_key, BinaryHeap};
+pub use in_place::{merge_sort_in_place, merge_sort_in_place_by, merge_sort_in_place_by_key};
+pub use intro::{intro_sort, intro_sort_by, intro_sort_by_key};
+pub use iterative::{
+    merge_sort_bottom_up, merge_sort_bottom_up_by, merge_sort_bottom_up_by_key,
+    quick_sort_iterative, quick_sort_iterative_by, quick_sort_iterative_by_key,
+};
+pub use kmerge::{kmerge, kmerge_by, kmerge_by_key, KMerge};
+pub use parallel::{
+    par_merge_sort, par_merge_sort_by, par_merge_sort_by_key, par_quick_sort, par_quick_sort_by,
+    par_quick_sort_by_key,
+};
+pub use permutation::{
+    apply_permutation, argsort, argsort_by, argsort_by_key, argsort_unstable, argsort_unstable_by,
+    argsort_unstable_by_key, inverse_permutation,
+};
+pub use radix::{
+    counting_sort, counting_sort_by_key, radix_sort_lsd, radix_sort_lsd_by_key, radix_sort_msd,
+    RadixKey,
+};
+pub use select::{
+    partial_sort, partial_sort_by, partial_sort_by_key, select_nth, select_nth_by,
+    select_nth_by_key, top_k, top_k_by, top_k_by_key,
+};
+pub use shell::{shell_sort, shell_sort_by, shell_sort_by_key, GapSequence};
+pub use small::{
+    branchless_insertion_sort, branchless_insertion_sort_by, branchless_insertion_sort_by_key,
+    sort_network, sort_network_by, sort_network_by_key,
+};
+pub use sorter::{
+    comparison_sorters, find_comparison_sorter, find_sorter, sorters, BranchlessInsertionSort,
+    BubbleSort, Complexity, CountingSort, HeapSort, InsertionSort, IntroSort, MergeSort,
+    MergeSortBottomUp, MergeSortInPlace, ParMergeSort, ParQuickSort, QuickSort, QuickSort3Way,
+    QuickSortIterative, RadixSortLsd, RadixSortMsd, SelectionSort, ShellSort, Sorter, TimSort,
+};
+pub use stats::{
+    branchless_insertion_sort_instrumented, bubble_sort_instrumented, counting_sort_instrumented,
+    heap_sort_instrumented, insertion_sort_instrumented, intro_sort_instrumented,
+    merge_sort_bottom_up_instrumented, merge_sort_in_place_instrumented, merge_sort_instrumented,
+    par_merge_sort_instrumented, par_quick_sort_instrumented, quick_sort_3way_instrumented,
+    quick_sort_instrumented, quick_sort_iterative_instrumented, radix_sort_lsd_instrumented,
+    radix_sort_msd_instrumented, selection_sort_instrumented, shell_sort_instrumented,
+    sort_network_instrumented, tim_sort_instrumented, SortStats,
+};
+pub use three_way::{quick_sort_3way, quick_sort_3way_by, quick_sort_3way_by_key};
+pub use tim::{tim_sort, tim_sort_by, tim_sort_by_key};
+pub use trace::{
+    bubble_sort_traced, insertion_sort_traced, merge_sort_traced, quick_sort_traced, replay_trace,
+    selection_sort_traced, SortEvent,
+};
+
 /// # 冒泡排序
 ///
 /// 每轮比较相邻两位，把大的元素交换到高位。
@@ -19,9 +99,64 @@
 /// assert_eq!(vec_2, vec![1,2,3,4,5]);
 /// ```
 ///
-pub fn bubble_sort<T>(vec: &mut Vec<T>)
+pub fn bubble_sort<T>(vec: &mut [T])
 where
     T: PartialOrd,
+{
+    bubble_sort_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 冒泡排序（自定义比较）
+///
+/// 与 [`bubble_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::bubble_sort_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// bubble_sort_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn bubble_sort_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    bubble_sort_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 冒泡排序（按键排序）
+///
+/// 与 [`bubble_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::bubble_sort_by_key;
+/// #
+/// let mut vec = vec![(3, 'a'), (1, 'b'), (2, 'c')];
+///
+/// bubble_sort_by_key(&mut vec, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (2, 'c'), (3, 'a')]);
+/// ```
+///
+pub fn bubble_sort_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    bubble_sort_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+fn bubble_sort_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
 {
     // 冒泡行为标志
     let mut is_bubbled;
@@ -31,11 +166,12 @@ where
 
         // 模拟窗口迭代
         for (x, y) in (1..(vec.len() - i)).enumerate() {
-            if vec[x] > vec[y] {
+            if less_at(vec, y, x, is_less, probe) {
                 // let temp = vec[x];
                 // vec[x] = vec[y];
                 // vec[y] = temp;
                 vec.swap(x, y);
+                probe.swap(x, y);
 
                 is_bubbled = true;
             }
@@ -62,40 +198,92 @@ where
 /// let mut vec_0: Vec<i32> = Vec::new();
 /// let mut vec_1 = vec![1];
 /// let mut vec_2 = vec![3,5,2,4,1];
+/// let mut vec_3 = vec!["b".to_string(), "c".to_string(), "a".to_string()];
 ///
 /// insertion_sort(&mut vec_0);
 /// insertion_sort(&mut vec_1);
 /// insertion_sort(&mut vec_2);
+/// insertion_sort(&mut vec_3);
 ///
 /// assert_eq!(vec_0, Vec::new());
 /// assert_eq!(vec_1, vec![1]);
 /// assert_eq!(vec_2, vec![1,2,3,4,5]);
+/// assert_eq!(vec_3, vec!["a", "b", "c"]);
 /// ```
 ///
-pub fn insertion_sort<T>(vec: &mut Vec<T>)
+pub fn insertion_sort<T>(vec: &mut [T])
 where
-    T: Copy + Ord,
+    T: Ord,
+{
+    insertion_sort_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 插入排序（自定义比较）
+///
+/// 与 [`insertion_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::insertion_sort_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// insertion_sort_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn insertion_sort_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    insertion_sort_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 插入排序（按键排序）
+///
+/// 与 [`insertion_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::insertion_sort_by_key;
+/// #
+/// let mut vec = vec![(3, 'a'), (1, 'b'), (2, 'c')];
+///
+/// insertion_sort_by_key(&mut vec, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (2, 'c'), (3, 'a')]);
+/// ```
+///
+pub fn insertion_sort_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    insertion_sort_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+fn insertion_sort_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
 {
     // 遍历无序部分
     for i in 1..vec.len() {
-        // 保存当前值
-        let temp = vec[i];
-        let mut idx: usize = 0;
-
-        // 从高到低，遍历有序部分，方便移位
-        for j in (0..i).rev() {
-            // 比当前值大，向右挪一位
-            // 比当前值小，就不挪，返回右边的空位
-            if vec[j] > temp {
-                vec[j + 1] = vec[j];
-            } else {
-                idx = j + 1;
-                break;
-            }
+        // 从高到低，遍历有序部分，找到当前值应在的位置
+        // 比当前值大的才越过，相等的不越过，保持稳定
+        let mut idx = i;
+        while idx > 0 && less_at(vec, i, idx - 1, is_less, probe) {
+            idx -= 1;
         }
 
-        // 当前值填入空位
-        vec[idx] = temp;
+        // 比当前值大的元素整体右移一位，当前值填入空位
+        if idx < i {
+            vec[idx..=i].rotate_right(1);
+            probe.insert(i, idx);
+        }
     }
 }
 
@@ -119,9 +307,64 @@ where
 /// assert_eq!(vec_2, vec![1,2,3,4,5]);
 /// ```
 ///
-pub fn selection_sort<T>(vec: &mut Vec<T>)
+pub fn selection_sort<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    selection_sort_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 选择排序（自定义比较）
+///
+/// 与 [`selection_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::selection_sort_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// selection_sort_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn selection_sort_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    selection_sort_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 选择排序（按键排序）
+///
+/// 与 [`selection_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::selection_sort_by_key;
+/// #
+/// let mut vec = vec![(3, 'a'), (1, 'b'), (2, 'c')];
+///
+/// selection_sort_by_key(&mut vec, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (2, 'c'), (3, 'a')]);
+/// ```
+///
+pub fn selection_sort_by_key<T, K, F>(vec: &mut [T], mut f: F)
 where
-    T: Copy + Ord,
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    selection_sort_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+fn selection_sort_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
 {
     // 当前最小元素的索引
     let mut idx;
@@ -131,7 +374,7 @@ where
 
         // 遍历寻找最小元素
         for j in (i + 1)..vec.len() {
-            if vec[j] < vec[idx] {
+            if less_at(vec, j, idx, is_less, probe) {
                 idx = j;
             }
         }
@@ -142,6 +385,7 @@ where
             // vec[idx] = vec[i];
             // vec[i] = temp;
             vec.swap(idx, i);
+            probe.swap(idx, i);
         }
     }
 }
@@ -151,7 +395,9 @@ where
 /// 用递归的方式，将大的数组拆成两个子数组，然后对子数组进行排序合并。
 ///
 /// 对子数组递归调用归并排序，在后续合并时，子数组已经是有序的。
-/// 只需要依序比较子数组的头部元素，将较小的填入新建的数组即可。
+/// 只需要依序比较子数组的头部元素，将较小的填回原数组即可。
+///
+/// 接收并返回 `Vec`，是 [`merge_sort_slice`] 的简单包装。
 ///
 /// ```
 /// # use algorithm_study::sorting::merge_sort;
@@ -169,56 +415,315 @@ where
 ///
 pub fn merge_sort<T>(mut vec: Vec<T>) -> Vec<T>
 where
-    T: Copy + Ord,
+    T: Ord,
+{
+    merge_sort_slice(&mut vec);
+    vec
+}
+
+/// # 归并排序（自定义比较）
+///
+/// 与 [`merge_sort`] 相同，但使用 `compare` 决定元素顺序。排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_by;
+/// #
+/// let vec = vec![3,5,2,4,1];
+///
+/// assert_eq!(merge_sort_by(vec, |a, b| b.cmp(a)), vec![5,4,3,2,1]);
+/// ```
+///
+pub fn merge_sort_by<T, F>(mut vec: Vec<T>, compare: F) -> Vec<T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_slice_by(&mut vec, compare);
+    vec
+}
+
+/// # 归并排序（按键排序）
+///
+/// 与 [`merge_sort`] 相同，但按 `f` 提取的键排序。排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_by_key;
+/// #
+/// let vec = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+///
+/// assert_eq!(
+///     merge_sort_by_key(vec, |x| x.0),
+///     vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]
+/// );
+/// ```
+///
+pub fn merge_sort_by_key<T, K, F>(mut vec: Vec<T>, f: F) -> Vec<T>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    merge_sort_slice_by_key(&mut vec, f);
+    vec
+}
+
+/// # 归并排序（切片版本）
+///
+/// 原地对切片排序，合并时把较短的一段移入辅助空间，再依序填回，
+/// 整个排序只分配一次辅助空间（长度为切片的一半）。
+/// 长度不超过 16 的子数组不再拆分，改用 [`branchless_insertion_sort`]。
+///
+/// 元素只会被移动，不会被复制，因此 `String` 等非 `Copy` 类型也可以排序。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_slice;
+/// #
+/// let mut vec = vec![3,5,2,4,1,6];
+/// let mut strs = vec!["b".to_string(), "c".to_string(), "a".to_string()];
+///
+/// // 只排序一部分
+/// merge_sort_slice(&mut vec[1..5]);
+/// merge_sort_slice(&mut strs);
+///
+/// assert_eq!(vec, vec![3,1,2,4,5,6]);
+/// assert_eq!(strs, vec!["a", "b", "c"]);
+/// ```
+///
+pub fn merge_sort_slice<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    merge_sort_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 归并排序（切片版本，自定义比较）
+///
+/// 与 [`merge_sort_slice`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_slice_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// merge_sort_slice_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn merge_sort_slice_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 归并排序（切片版本，按键排序）
+///
+/// 与 [`merge_sort_slice`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_slice_by_key;
+/// #
+/// let mut vec = vec!["ccc".to_string(), "a".to_string(), "bb".to_string()];
+///
+/// merge_sort_slice_by_key(&mut vec, |s| s.len());
+///
+/// assert_eq!(vec, vec!["a", "bb", "ccc"]);
+/// ```
+///
+pub fn merge_sort_slice_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    merge_sort_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+/// 归并排序、快速选择等算法中，区间长度不超过该值时改用插入排序
+const INSERTION_LEN: usize = 16;
+
+fn merge_sort_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    // 辅助空间只分配一次，每次合并时复用
+    let mut buf = Vec::with_capacity(vec.len() / 2);
+    probe.alloc(vec.len() / 2);
+    merge_sort_with_buf(vec, &mut buf, is_less, probe);
+
+    fn merge_sort_with_buf<T, F, P>(vec: &mut [T], buf: &mut Vec<T>, is_less: &mut F, probe: &P)
+    where
+        F: FnMut(&T, &T) -> bool,
+        P: Probe,
+    {
+        let len = vec.len();
+
+        // 递归终止条件：短区间直接用无分支插入排序，它也是稳定的
+        if len <= INSERTION_LEN {
+            small::branchless_insertion_sort_impl(vec, is_less, probe);
+            return;
+        }
+
+        // 数组分成两段，向下递归
+        let mid = len / 2;
+        probe.enter();
+        merge_sort_with_buf(&mut vec[..mid], buf, is_less, probe);
+        probe.shift(mid);
+        merge_sort_with_buf(&mut vec[mid..], buf, is_less, probe);
+        probe.unshift(mid);
+        probe.leave();
+
+        // 合并数组
+        merge(vec, mid, buf, is_less, probe);
+    }
+}
+
+/// 合并 `vec[..mid]` 与 `vec[mid..]` 两段有序区间。
+///
+/// 较短的一段先移入 `buf`，再依序把较小的元素填回 `vec`，
+/// 相等时优先取左段的元素，保证稳定。
+fn merge<T, F, P>(vec: &mut [T], mid: usize, buf: &mut Vec<T>, is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
 {
     let len = vec.len();
 
-    // 递归终止条件
-    if len <= 1 {
-        return vec;
+    if mid == 0 || mid >= len {
+        return;
+    }
+
+    probe.merge_range(0, mid, len);
+
+    // 左段末尾不大于右段开头，说明已经有序
+    if !less_at(vec, mid, mid - 1, is_less, probe) {
+        return;
     }
 
-    // 数组分成两段，向下递归
-    // 将返回的数组转换成可以Peek的迭代器，方便合并
-    let mut second_half = merge_sort(vec.split_off(vec.len() / 2))
-        .into_iter()
-        .peekable();
-    let mut first_half = merge_sort(vec).into_iter().peekable();
+    // buf 只当作未初始化的内存使用，长度始终为 0，不会重复 drop 其中的元素
+    buf.clear();
+    buf.reserve(mid.min(len - mid));
 
-    let mut sorted: Vec<T> = Vec::with_capacity(len);
+    let v = vec.as_mut_ptr();
+    let b = buf.as_mut_ptr();
 
-    // 合并数组
-    loop {
-        match (first_half.peek(), second_half.peek()) {
-            (Some(&x), Some(&y)) if x > y => {
-                sorted.push(y);
-                second_half.next();
+    // 较短一段移入 buf 算一次移动，之后每个写回 vec 的元素再算一次
+    probe.moves(mid.min(len - mid));
+
+    // SAFETY: 两段区间都在 vec 范围内，buf 的容量足够放下较短的一段。
+    // 比较函数 panic 时，hole 在 drop 时会把 buf 中剩余的元素移回 vec 的空位，
+    // 保证每个元素恰好出现一次。
+    unsafe {
+        let v_mid = v.add(mid);
+        let v_end = v.add(len);
+
+        if mid <= len - mid {
+            // 左段较短：移入 buf，从前往后合并
+            // buf 中第 k 个元素合并前位于 k，右段的元素在被取出前都还在原位
+            ptr::copy_nonoverlapping(v, b, mid);
+            let mut hole = MergeHole {
+                start: b,
+                end: b.add(mid),
+                dest: v,
+            };
+            let mut right = v_mid;
+
+            while hole.start < hole.end && right < v_end {
+                let r = right.offset_from(v) as usize;
+                let l = hole.start.offset_from(b) as usize;
+                probe.compare_at(r, l);
+
+                // 右段严格更小时才取右段
+                let (src, from) = if is_less(&*right, &*hole.start) {
+                    right = right.add(1);
+                    (right.sub(1), r)
+                } else {
+                    hole.start = hole.start.add(1);
+                    (hole.start.sub(1), l)
+                };
+                probe.write(hole.dest.offset_from(v) as usize, from);
+                ptr::copy_nonoverlapping(src, hole.dest, 1);
+                hole.dest = hole.dest.add(1);
             }
-            (Some(&x), Some(_)) => {
-                sorted.push(x);
-                first_half.next();
+
+            // buf 中剩余的元素由 hole 移回
+            let rest = hole.start.offset_from(b) as usize;
+            let dest = hole.dest.offset_from(v) as usize;
+            for k in rest..mid {
+                probe.write(dest + k - rest, k);
             }
-            (None, _) => {
-                for i in second_half {
-                    sorted.push(i);
-                }
-                return sorted;
+        } else {
+            // 右段较短：移入 buf，从后往前合并
+            // buf 中第 k 个元素合并前位于 mid + k，左段的元素在被取出前都还在原位
+            ptr::copy_nonoverlapping(v_mid, b, len - mid);
+            let mut hole = MergeHole {
+                start: b,
+                end: b.add(len - mid),
+                dest: v_mid,
+            };
+            let mut out = v_end;
+
+            while v < hole.dest && hole.start < hole.end {
+                let r = mid + hole.end.offset_from(b) as usize - 1;
+                let l = hole.dest.offset_from(v) as usize - 1;
+                probe.compare_at(r, l);
+
+                // 左段严格更大时才取左段
+                out = out.sub(1);
+                let from = if is_less(&*hole.end.sub(1), &*hole.dest.sub(1)) {
+                    hole.dest = hole.dest.sub(1);
+                    ptr::copy_nonoverlapping(hole.dest, out, 1);
+                    l
+                } else {
+                    hole.end = hole.end.sub(1);
+                    ptr::copy_nonoverlapping(hole.end, out, 1);
+                    r
+                };
+                probe.write(out.offset_from(v) as usize, from);
             }
-            (_, None) => {
-                for i in first_half {
-                    sorted.push(i);
-                }
-                return sorted;
+
+            // buf 中剩余的元素由 hole 移回
+            let rest = hole.end.offset_from(b) as usize;
+            let dest = hole.dest.offset_from(v) as usize;
+            for k in 0..rest {
+                probe.write(dest + k, mid + k);
             }
         }
     }
 }
 
+/// 合并过程中 buf 里 `[start, end)` 是还没填回的元素，`dest` 是它们在原数组中的空位。
+///
+/// drop 时把剩余元素移回空位，合并正常结束或比较函数 panic 时都能保证每个元素恰好出现一次。
+struct MergeHole<T> {
+    start: *mut T,
+    end: *mut T,
+    dest: *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+    fn drop(&mut self) {
+        // SAFETY: 空位的长度恰好等于 buf 中剩余元素的个数
+        unsafe {
+            let len = self.end.offset_from(self.start) as usize;
+            ptr::copy_nonoverlapping(self.start, self.dest, len);
+        }
+    }
+}
+
 /// # 快速排序
 ///
 /// 选择一个元素作为分区点，将小于它的元素移动到它左侧，大于的移到右侧
 /// 然后对左侧&右侧的子数组递归调用快速排序
 ///
+/// 分区点取三数中位数，已有序或逆序的数组也能分得均匀；
+/// 若分区层数超过 `2·log2(n)`，剩余部分改用堆排序，最坏也是 O(n log n)。
+/// 只对较短的一侧递归，递归深度不超过 `log2(n)`，不会栈溢出。
+/// 长度不超过 16 的区间不再分区，改用 [`sort_network`]。
+///
 /// ```
 /// # use algorithm_study::sorting::quick_sort;
 /// #
@@ -226,61 +731,309 @@ where
 /// let mut vec_1 = vec![1];
 /// let mut vec_2 = vec![2,1];
 /// let mut vec_3 = vec![3,5,2,4,1];
+/// let mut vec_4 = vec![1,3,2,5,4];
 ///
 /// quick_sort(&mut vec_0);
 /// quick_sort(&mut vec_1);
 /// quick_sort(&mut vec_2);
 /// quick_sort(&mut vec_3);
+/// quick_sort(&mut vec_4);
 ///
 /// assert_eq!(vec_0, Vec::new());
 /// assert_eq!(vec_1, vec![1]);
 /// assert_eq!(vec_2, vec![1,2]);
 /// assert_eq!(vec_3, vec![1,2,3,4,5]);
+/// assert_eq!(vec_4, vec![1,2,3,4,5]);
+/// ```
+///
+pub fn quick_sort<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    quick_sort_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 快速排序（自定义比较）
+///
+/// 与 [`quick_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
 /// ```
+/// # use algorithm_study::sorting::quick_sort_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
 ///
-pub fn quick_sort<T>(vec: &mut Vec<T>)
+/// quick_sort_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn quick_sort_by<T, F>(vec: &mut [T], mut compare: F)
 where
-    T: Copy + Ord,
+    F: FnMut(&T, &T) -> Ordering,
 {
-    quick_sort_with_idx(vec, 0, vec.len());
+    quick_sort_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
 
-    fn quick_sort_with_idx<T>(vec: &mut Vec<T>, start: usize, end: usize)
+/// # 快速排序（按键排序）
+///
+/// 与 [`quick_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::quick_sort_by_key;
+/// #
+/// let mut vec = vec![(3, 'a'), (1, 'b'), (2, 'c')];
+///
+/// quick_sort_by_key(&mut vec, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (2, 'c'), (3, 'a')]);
+/// ```
+///
+pub fn quick_sort_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    quick_sort_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+fn quick_sort_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    quick_sort_with_idx(
+        vec,
+        0,
+        vec.len(),
+        depth_limit(vec.len()),
+        SmallSort::Network,
+        is_less,
+        probe,
+    );
+}
+
+/// 快速排序中区间长度不超过该值时改用排序网络
+const NETWORK_LEN: usize = 16;
+
+/// 快速排序在短区间上改用的排序
+#[derive(Debug, Clone, Copy)]
+enum SmallSort {
+    /// 长度不超过 [`INSERTION_LEN`] 时用插入排序
+    Insertion,
+    /// 长度不超过 [`NETWORK_LEN`] 时用排序网络
+    Network,
+}
+
+impl SmallSort {
+    fn max_len(self) -> usize {
+        match self {
+            SmallSort::Insertion => INSERTION_LEN,
+            SmallSort::Network => NETWORK_LEN,
+        }
+    }
+
+    fn sort<T, F, P>(self, vec: &mut [T], is_less: &mut F, probe: &P)
     where
-        T: Copy + Ord,
+        F: FnMut(&T, &T) -> bool,
+        P: Probe,
     {
-        if start + 1 >= end {
+        match self {
+            SmallSort::Insertion => insertion_sort_impl(vec, is_less, probe),
+            SmallSort::Network => small::sort_network_impl(vec, is_less, probe),
+        }
+    }
+}
+
+/// 快速排序允许的最大分区层数，约为 `2·log2(n)`。
+fn depth_limit(len: usize) -> u32 {
+    2 * (usize::BITS - len.leading_zeros())
+}
+
+/// 对 `vec[start..end]` 做快速排序。
+///
+/// - 分区层数超过 `limit`，说明 pivot 选得很差，剩余部分改用堆排序；
+/// - 区间长度不超过 `small` 规定的长度时，改用对应的排序；
+/// - 只对较短的一侧递归，较长的一侧在循环里继续处理，递归深度不超过 `log2(n)`。
+fn quick_sort_with_idx<T, F, P>(
+    vec: &mut [T],
+    mut start: usize,
+    mut end: usize,
+    mut limit: u32,
+    small: SmallSort,
+    is_less: &mut F,
+    probe: &P,
+) where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    while start + 1 < end {
+        if end - start <= small.max_len() {
+            probe.shift(start);
+            small.sort(&mut vec[start..end], is_less, probe);
+            probe.unshift(start);
             return;
         }
 
-        let mut lm = start + 1;
-        let mut rm = end - 1;
+        if limit == 0 {
+            probe.shift(start);
+            heap::heap_sort_impl(&mut vec[start..end], is_less, probe);
+            probe.unshift(start);
+            return;
+        }
+        limit -= 1;
 
-        loop {
-            // 找到大于pivot的左侧元素
-            // 先比较索引，短路计算，避免溢出
-            while lm <= rm && vec[lm] <= vec[start] {
-                lm += 1;
-            }
+        let mid = partition(vec, start, end, is_less, probe);
 
-            // 找到小于pivot的右侧元素
-            while lm <= rm && vec[rm] >= vec[start] {
-                rm -= 1;
-            }
+        probe.enter();
+        if mid - start < end - mid {
+            quick_sort_with_idx(vec, start, mid, limit, small, is_less, probe);
+            start = mid + 1;
+        } else {
+            quick_sort_with_idx(vec, mid + 1, end, limit, small, is_less, probe);
+            end = mid;
+        }
+        probe.leave();
+    }
+}
 
-            // 碰头了，说明没找到
-            if lm > rm {
-                break;
-            }
+/// 选出 pivot 并对 `vec[start..end]` 分区，返回 pivot 最终所在的下标。
+///
+/// 分区后左侧元素都不大于 pivot，右侧元素都不小于 pivot。
+fn partition<T, F, P>(vec: &mut [T], start: usize, end: usize, is_less: &mut F, probe: &P) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let pivot = choose_pivot(vec, start, end, is_less, probe);
+    partition_at(vec, start, end, pivot, is_less, probe)
+}
+
+/// 以 `vec[pivot]` 为分区点对 `vec[start..end]` 分区，返回 pivot 最终所在的下标。
+fn partition_at<T, F, P>(
+    vec: &mut [T],
+    start: usize,
+    end: usize,
+    pivot: usize,
+    is_less: &mut F,
+    probe: &P,
+) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    // pivot 换到最前面
+    probe.partition_start(start, end, pivot);
+    vec.swap(start, pivot);
+    probe.swap(start, pivot);
+
+    let mut lm = start + 1;
+    let mut rm = end - 1;
+
+    loop {
+        // 找到不小于pivot的左侧元素
+        // 先比较索引，短路计算，避免溢出
+        while lm <= rm && less_at(vec, lm, start, is_less, probe) {
+            lm += 1;
+        }
 
-            // 交换
-            vec.swap(lm, rm);
+        // 找到不大于pivot的右侧元素
+        while lm <= rm && less_at(vec, start, rm, is_less, probe) {
+            rm -= 1;
+        }
+
+        // 碰头了，说明没找到
+        if lm > rm {
+            break;
         }
 
         // 交换
-        vec.swap(start, lm - 1);
+        // 与pivot相等的元素也会交换，大量重复元素时两侧仍能分得均匀
+        vec.swap(lm, rm);
+        probe.swap(lm, rm);
+        lm += 1;
+        rm -= 1;
+    }
+
+    // 交换，pivot 在 lm - 1 处就位
+    vec.swap(start, lm - 1);
+    probe.swap(start, lm - 1);
+    lm - 1
+}
+
+/// 选择 pivot 的下标。
+///
+/// 取首、中、尾三个元素的中位数，对已有序或逆序的数组也能分得均匀；
+/// 区间较长时，先在三段里各取三数中位数，再取它们的中位数（九数中位数）。
+fn choose_pivot<T, F, P>(vec: &[T], start: usize, end: usize, is_less: &mut F, probe: &P) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    const NINTHER_LEN: usize = 128;
+
+    let len = end - start;
+    let (a, b, c) = (start, start + len / 2, end - 1);
+
+    if len >= NINTHER_LEN {
+        let step = len / 8;
+        let a = median_of_three(vec, a, a + step, a + 2 * step, is_less, probe);
+        let b = median_of_three(vec, b - step, b, b + step, is_less, probe);
+        let c = median_of_three(vec, c - 2 * step, c - step, c, is_less, probe);
+        median_of_three(vec, a, b, c, is_less, probe)
+    } else {
+        median_of_three(vec, a, b, c, is_less, probe)
+    }
+}
+
+/// 返回 `vec[a]`、`vec[b]`、`vec[c]` 三者中位数的下标。
+fn median_of_three<T, F, P>(
+    vec: &[T],
+    a: usize,
+    b: usize,
+    c: usize,
+    is_less: &mut F,
+    probe: &P,
+) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let ab = less_at(vec, a, b, is_less, probe);
+    let bc = less_at(vec, b, c, is_less, probe);
 
-        // 分区递归
-        quick_sort_with_idx(vec, start, lm);
-        quick_sort_with_idx(vec, lm + 1, end);
+    // a < b < c 或 a >= b >= c
+    if ab == bc {
+        return b;
     }
+
+    // b 是最大或最小的，中位数在 a、c 之间
+    let ac = less_at(vec, a, c, is_less, probe);
+    if ab == ac {
+        c
+    } else {
+        a
+    }
+}
+
+/// 比较 `vec[i]` 与 `vec[j]`，并把两者的下标告诉探针。
+fn less_at<T, F, P>(vec: &[T], i: usize, j: usize, is_less: &mut F, probe: &P) -> bool
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    probe.compare_at(i, j);
+    is_less(&vec[i], &vec[j])
+}
+
+/// 测试用的线性同余伪随机数，各个测试传入不同的种子
+#[cfg(test)]
+pub(crate) fn random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 16
 }
@@ -0,0 +1,329 @@
+use super::probe::Fork;
+use super::{depth_limit, heap, merge, merge_sort_impl, partition, quick_sort_with_idx, SmallSort};
+use std::cmp::Ordering;
+use std::panic;
+use std::thread;
+
+/// 区间长度不超过该值时不再拆分线程，直接调用顺序版本
+const PAR_THRESHOLD: usize = 4096;
+
+/// # 并行归并排序
+///
+/// 与 [`merge_sort_slice`](super::merge_sort_slice) 的拆分方式相同：左半部分交给新线程，
+/// 当前线程处理右半部分，两半都排好后再合并。`threads` 个线程用完，
+/// 或区间长度不超过 4096 时，直接调用顺序版本。
+///
+/// `threads` 为 0 时使用 [`std::thread::available_parallelism`]。
+/// 排序是稳定的，结果与顺序版本完全相同。
+///
+/// ```
+/// # use algorithm_study::sorting::par_merge_sort;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![3,5,2,4,1];
+/// let mut vec_2: Vec<i32> = (0..100_000).rev().collect();
+///
+/// par_merge_sort(&mut vec_0, 4);
+/// par_merge_sort(&mut vec_1, 4);
+/// par_merge_sort(&mut vec_2, 4);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1,2,3,4,5]);
+/// assert_eq!(vec_2, (0..100_000).collect::<Vec<_>>());
+/// ```
+///
+pub fn par_merge_sort<T>(vec: &mut [T], threads: usize)
+where
+    T: Ord + Send,
+{
+    par_merge_sort_impl(vec, threads_or_default(threads), &|a: &T, b: &T| a < b, &());
+}
+
+/// # 并行归并排序（自定义比较）
+///
+/// 与 [`par_merge_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::par_merge_sort_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// par_merge_sort_by(&mut vec, 2, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn par_merge_sort_by<T, F>(vec: &mut [T], threads: usize, compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let is_less = |a: &T, b: &T| compare(a, b) == Ordering::Less;
+    par_merge_sort_impl(vec, threads_or_default(threads), &is_less, &());
+}
+
+/// # 并行归并排序（按键排序）
+///
+/// 与 [`par_merge_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::par_merge_sort_by_key;
+/// #
+/// let mut vec = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+///
+/// par_merge_sort_by_key(&mut vec, 2, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+/// ```
+///
+pub fn par_merge_sort_by_key<T, K, F>(vec: &mut [T], threads: usize, f: F)
+where
+    T: Send,
+    K: Ord,
+    F: Fn(&T) -> K + Sync,
+{
+    let is_less = |a: &T, b: &T| f(a) < f(b);
+    par_merge_sort_impl(vec, threads_or_default(threads), &is_less, &());
+}
+
+pub(super) fn par_merge_sort_impl<T, F, P>(vec: &mut [T], threads: usize, is_less: &F, probe: &P)
+where
+    T: Send,
+    F: Fn(&T, &T) -> bool + Sync,
+    P: Fork + Send,
+{
+    let len = vec.len();
+    let counted = &mut |a: &T, b: &T| {
+        probe.compare();
+        is_less(a, b)
+    };
+
+    if threads <= 1 || len <= PAR_THRESHOLD {
+        merge_sort_impl(vec, counted, probe);
+        return;
+    }
+
+    // 与顺序版本相同，从中间拆成两段
+    let mid = len / 2;
+    let (left, right) = vec.split_at_mut(mid);
+    let left_threads = threads / 2;
+
+    let child = probe.fork();
+    let child = thread::scope(|s| {
+        let handle = s.spawn(move || {
+            child.enter();
+            par_merge_sort_impl(left, left_threads, is_less, &child);
+            child.leave();
+            child
+        });
+        probe.enter();
+        par_merge_sort_impl(right, threads - left_threads, is_less, probe);
+        probe.leave();
+        handle.join()
+    });
+    probe.join(child.unwrap_or_else(|e| panic::resume_unwind(e)));
+
+    let mut buf = Vec::with_capacity(mid);
+    probe.alloc(mid);
+    merge(vec, mid, &mut buf, counted, probe);
+}
+
+/// # 并行快速排序
+///
+/// 与 [`quick_sort`](super::quick_sort) 的分区方式完全相同：分区后左侧交给新线程，
+/// 当前线程处理右侧。`threads` 个线程用完，或区间长度不超过 4096 时，
+/// 直接调用顺序版本。
+///
+/// `threads` 为 0 时使用 [`std::thread::available_parallelism`]。
+/// 每个区间的处理过程与顺序版本一致，即使有相等的元素，结果也与顺序版本完全相同。
+///
+/// ```
+/// # use algorithm_study::sorting::par_quick_sort;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![3,5,2,4,1];
+/// let mut vec_2: Vec<i32> = (0..100_000).rev().collect();
+///
+/// par_quick_sort(&mut vec_0, 4);
+/// par_quick_sort(&mut vec_1, 4);
+/// par_quick_sort(&mut vec_2, 4);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1,2,3,4,5]);
+/// assert_eq!(vec_2, (0..100_000).collect::<Vec<_>>());
+/// ```
+///
+pub fn par_quick_sort<T>(vec: &mut [T], threads: usize)
+where
+    T: Ord + Send,
+{
+    par_quick_sort_impl(vec, threads_or_default(threads), &|a: &T, b: &T| a < b, &());
+}
+
+/// # 并行快速排序（自定义比较）
+///
+/// 与 [`par_quick_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::par_quick_sort_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// par_quick_sort_by(&mut vec, 2, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn par_quick_sort_by<T, F>(vec: &mut [T], threads: usize, compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let is_less = |a: &T, b: &T| compare(a, b) == Ordering::Less;
+    par_quick_sort_impl(vec, threads_or_default(threads), &is_less, &());
+}
+
+/// # 并行快速排序（按键排序）
+///
+/// 与 [`par_quick_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::par_quick_sort_by_key;
+/// #
+/// let mut vec = vec![(3, 'a'), (1, 'b'), (2, 'c')];
+///
+/// par_quick_sort_by_key(&mut vec, 2, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (2, 'c'), (3, 'a')]);
+/// ```
+///
+pub fn par_quick_sort_by_key<T, K, F>(vec: &mut [T], threads: usize, f: F)
+where
+    T: Send,
+    K: Ord,
+    F: Fn(&T) -> K + Sync,
+{
+    let is_less = |a: &T, b: &T| f(a) < f(b);
+    par_quick_sort_impl(vec, threads_or_default(threads), &is_less, &());
+}
+
+pub(super) fn par_quick_sort_impl<T, F, P>(vec: &mut [T], threads: usize, is_less: &F, probe: &P)
+where
+    T: Send,
+    F: Fn(&T, &T) -> bool + Sync,
+    P: Fork + Send,
+{
+    par_quick_sort_with_limit(vec, threads, depth_limit(vec.len()), is_less, probe);
+
+    fn par_quick_sort_with_limit<T, F, P>(
+        vec: &mut [T],
+        threads: usize,
+        mut limit: u32,
+        is_less: &F,
+        probe: &P,
+    ) where
+        T: Send,
+        F: Fn(&T, &T) -> bool + Sync,
+        P: Fork + Send,
+    {
+        let len = vec.len();
+        let counted = &mut |a: &T, b: &T| {
+            probe.compare();
+            is_less(a, b)
+        };
+
+        if threads <= 1 || len <= PAR_THRESHOLD {
+            quick_sort_with_idx(vec, 0, len, limit, SmallSort::Network, counted, probe);
+            return;
+        }
+
+        // 以下步骤与顺序版本的一轮循环相同
+        if limit == 0 {
+            heap::heap_sort_impl(vec, counted, probe);
+            return;
+        }
+        limit -= 1;
+
+        let mid = partition(vec, 0, len, counted, probe);
+        let (left, right) = vec.split_at_mut(mid);
+        let right = &mut right[1..];
+        let left_threads = threads / 2;
+
+        let child = probe.fork();
+        let child = thread::scope(|s| {
+            let handle = s.spawn(move || {
+                child.enter();
+                par_quick_sort_with_limit(left, left_threads, limit, is_less, &child);
+                child.leave();
+                child
+            });
+            probe.enter();
+            par_quick_sort_with_limit(right, threads - left_threads, limit, is_less, probe);
+            probe.leave();
+            handle.join()
+        });
+        probe.join(child.unwrap_or_else(|e| panic::resume_unwind(e)));
+    }
+}
+
+/// `threads` 为 0 时取可用的并行度
+pub(super) fn threads_or_default(threads: usize) -> usize {
+    match threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::{merge_sort_slice_by_key, quick_sort_by_key, random};
+
+    fn random_pairs(len: usize, range: u64, seed: &mut u64) -> Vec<(u64, usize)> {
+        (0..len).map(|i| (random(seed) % range, i)).collect()
+    }
+
+    #[test]
+    fn test_same_as_sequential() {
+        let mut seed = 42;
+
+        for len in [0, 1, 100, PAR_THRESHOLD + 1, 50_000] {
+            for range in [3, 1000, u64::MAX] {
+                let vec = random_pairs(len, range, &mut seed);
+
+                for threads in [1, 2, 3, 8] {
+                    // 只按键比较，相等元素的排列顺序也要与顺序版本一致
+                    let mut expected = vec.clone();
+                    quick_sort_by_key(&mut expected, |x| x.0);
+                    let mut sorted = vec.clone();
+                    par_quick_sort_by_key(&mut sorted, threads, |x| x.0);
+                    assert_eq!(expected, sorted);
+
+                    let mut expected = vec.clone();
+                    merge_sort_slice_by_key(&mut expected, |x| x.0);
+                    let mut sorted = vec.clone();
+                    par_merge_sort_by_key(&mut sorted, threads, |x| x.0);
+                    assert_eq!(expected, sorted);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_threads() {
+        let mut seed = 7;
+        let vec: Vec<String> = (0..20_000)
+            .map(|_| (random(&mut seed) % 1000).to_string())
+            .collect();
+        let mut expected = vec.clone();
+        expected.sort();
+
+        let mut sorted = vec.clone();
+        par_merge_sort(&mut sorted, 0);
+        assert_eq!(expected, sorted);
+
+        let mut sorted = vec.clone();
+        par_quick_sort(&mut sorted, 0);
+        assert_eq!(expected, sorted);
+    }
+}
@@ -0,0 +1,306 @@
+use super::{merge_sort_impl, quick_sort_impl};
+use std::cmp::Ordering;
+
+/// # 排序下标
+///
+/// 返回能把 `vec` 排好序的下标顺序：`vec[idx[0]]`、`vec[idx[1]]`…… 是升序的，`vec` 本身不变。
+///
+/// 用 [`merge_sort`](super::merge_sort) 对下标排序，是稳定的：相等的元素按下标升序排列。
+/// 常与 [`apply_permutation`] 一起使用，按同一个顺序重排几个平行的数组。
+///
+/// ```
+/// # use algorithm_study::sorting::{apply_permutation, argsort};
+/// #
+/// let mut ages = vec![30, 25, 30, 20];
+/// let mut names = vec!["alice", "bob", "carol", "dave"];
+///
+/// let idx = argsort(&ages);
+/// assert_eq!(idx, vec![3, 1, 0, 2]);
+///
+/// apply_permutation(&mut ages, &idx);
+/// apply_permutation(&mut names, &idx);
+///
+/// assert_eq!(ages, vec![20, 25, 30, 30]);
+/// assert_eq!(names, vec!["dave", "bob", "alice", "carol"]);
+/// ```
+///
+pub fn argsort<T>(vec: &[T]) -> Vec<usize>
+where
+    T: Ord,
+{
+    let mut idx: Vec<usize> = (0..vec.len()).collect();
+    merge_sort_impl(&mut idx, &mut |&a: &usize, &b: &usize| vec[a] < vec[b], &());
+    idx
+}
+
+/// # 排序下标（自定义比较）
+///
+/// 与 [`argsort`] 相同，但使用 `compare` 决定元素顺序。排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::argsort_by;
+/// #
+/// let vec = vec![3, 1, 3, 2];
+///
+/// assert_eq!(argsort_by(&vec, |a, b| b.cmp(a)), vec![0, 2, 3, 1]);
+/// ```
+///
+pub fn argsort_by<T, F>(vec: &[T], mut compare: F) -> Vec<usize>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut idx: Vec<usize> = (0..vec.len()).collect();
+    merge_sort_impl(
+        &mut idx,
+        &mut |&a: &usize, &b: &usize| compare(&vec[a], &vec[b]) == Ordering::Less,
+        &(),
+    );
+    idx
+}
+
+/// # 排序下标（按键排序）
+///
+/// 与 [`argsort`] 相同，但按 `f` 提取的键排序。排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::argsort_by_key;
+/// #
+/// let words = vec!["ccc", "a", "bb", "d"];
+///
+/// assert_eq!(argsort_by_key(&words, |s| s.len()), vec![1, 3, 2, 0]);
+/// ```
+///
+pub fn argsort_by_key<T, K, F>(vec: &[T], mut f: F) -> Vec<usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    let mut idx: Vec<usize> = (0..vec.len()).collect();
+    merge_sort_impl(
+        &mut idx,
+        &mut |&a: &usize, &b: &usize| f(&vec[a]) < f(&vec[b]),
+        &(),
+    );
+    idx
+}
+
+/// # 排序下标（不稳定）
+///
+/// 与 [`argsort`] 相同，但用 [`quick_sort`](super::quick_sort) 对下标排序，
+/// 不需要辅助空间，相等元素的下标顺序不确定。
+///
+/// ```
+/// # use algorithm_study::sorting::argsort_unstable;
+/// #
+/// let vec = vec![30, 25, 10, 20];
+///
+/// assert_eq!(argsort_unstable(&vec), vec![2, 3, 1, 0]);
+/// ```
+///
+pub fn argsort_unstable<T>(vec: &[T]) -> Vec<usize>
+where
+    T: Ord,
+{
+    let mut idx: Vec<usize> = (0..vec.len()).collect();
+    quick_sort_impl(&mut idx, &mut |&a: &usize, &b: &usize| vec[a] < vec[b], &());
+    idx
+}
+
+/// # 排序下标（不稳定，自定义比较）
+///
+/// 与 [`argsort_unstable`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::argsort_unstable_by;
+/// #
+/// let vec = vec![3, 1, 4, 2];
+///
+/// assert_eq!(argsort_unstable_by(&vec, |a, b| b.cmp(a)), vec![2, 0, 3, 1]);
+/// ```
+///
+pub fn argsort_unstable_by<T, F>(vec: &[T], mut compare: F) -> Vec<usize>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut idx: Vec<usize> = (0..vec.len()).collect();
+    quick_sort_impl(
+        &mut idx,
+        &mut |&a: &usize, &b: &usize| compare(&vec[a], &vec[b]) == Ordering::Less,
+        &(),
+    );
+    idx
+}
+
+/// # 排序下标（不稳定，按键排序）
+///
+/// 与 [`argsort_unstable`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::argsort_unstable_by_key;
+/// #
+/// let vec = vec![-3i32, 1, -2];
+///
+/// assert_eq!(argsort_unstable_by_key(&vec, |x| x.abs()), vec![1, 2, 0]);
+/// ```
+///
+pub fn argsort_unstable_by_key<T, K, F>(vec: &[T], mut f: F) -> Vec<usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    let mut idx: Vec<usize> = (0..vec.len()).collect();
+    quick_sort_impl(
+        &mut idx,
+        &mut |&a: &usize, &b: &usize| f(&vec[a]) < f(&vec[b]),
+        &(),
+    );
+    idx
+}
+
+/// # 应用置换
+///
+/// 原地重排 `vec`，使新的 `vec[i]` 是原来的 `vec[perm[i]]`。
+///
+/// 把置换分解成若干个环，沿着每个环依次交换，每个元素最多被交换一次，
+/// 时间复杂度 O(n)，只需要 n 个 `bool` 记录哪些位置已经就位。
+///
+/// `perm` 的长度与 `vec` 不同，或者不是 `0..n` 的一个排列时 panic，此时 `vec` 不会被改动。
+///
+/// ```
+/// # use algorithm_study::sorting::apply_permutation;
+/// #
+/// let mut vec = vec!['a', 'b', 'c', 'd'];
+///
+/// apply_permutation(&mut vec, &[2, 0, 3, 1]);
+///
+/// assert_eq!(vec, vec!['c', 'a', 'd', 'b']);
+/// ```
+///
+pub fn apply_permutation<T>(vec: &mut [T], perm: &[usize]) {
+    assert_eq!(
+        vec.len(),
+        perm.len(),
+        "permutation length does not match slice length"
+    );
+
+    // 先检查 perm 是一个排列，之后 placed[i] 为 false 表示 vec[i] 已经就位
+    let mut placed = check_permutation(perm);
+
+    for start in 0..vec.len() {
+        if !placed[start] {
+            continue;
+        }
+
+        // 沿着环把每个位置需要的元素换过来，最后一个位置需要的正是 start 原来的元素
+        let mut i = start;
+        loop {
+            placed[i] = false;
+            let j = perm[i];
+            if j == start {
+                break;
+            }
+            vec.swap(i, j);
+            i = j;
+        }
+    }
+}
+
+/// # 逆置换
+///
+/// 返回 `perm` 的逆：`inv[perm[i]] == i`。
+///
+/// 对 [`argsort`] 的结果求逆，得到每个元素排序后所在的位置，即它的排名。
+/// `perm` 不是 `0..n` 的一个排列时 panic。
+///
+/// ```
+/// # use algorithm_study::sorting::{argsort, inverse_permutation};
+/// #
+/// let scores = vec![70, 95, 80];
+///
+/// let rank = inverse_permutation(&argsort(&scores));
+///
+/// assert_eq!(rank, vec![0, 2, 1]);
+/// ```
+///
+pub fn inverse_permutation(perm: &[usize]) -> Vec<usize> {
+    check_permutation(perm);
+
+    let mut inv = vec![0; perm.len()];
+    for (i, &p) in perm.iter().enumerate() {
+        inv[p] = i;
+    }
+    inv
+}
+
+/// 检查 `perm` 是 `0..n` 的一个排列，返回全为 `true` 的标记数组
+fn check_permutation(perm: &[usize]) -> Vec<bool> {
+    let mut seen = vec![false; perm.len()];
+    for &p in perm {
+        assert!(
+            p < perm.len() && !seen[p],
+            "not a permutation: index {} is out of range or repeated",
+            p
+        );
+        seen[p] = true;
+    }
+    seen
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::random;
+
+    #[test]
+    fn test_argsort() {
+        let mut seed = 3;
+
+        for len in [0, 1, 2, 10, 100, 1000] {
+            let vec: Vec<u64> = (0..len).map(|_| random(&mut seed) % 20).collect();
+
+            let idx = argsort(&vec);
+            let mut expected: Vec<usize> = (0..len).collect();
+            expected.sort_by_key(|&i| vec[i]);
+            assert_eq!(idx, expected);
+
+            let idx = argsort_unstable(&vec);
+            assert!(idx.windows(2).all(|w| vec[w[0]] <= vec[w[1]]));
+            assert_eq!(inverse_permutation(&inverse_permutation(&idx)), idx);
+        }
+    }
+
+    #[test]
+    fn test_apply_permutation() {
+        let mut seed = 5;
+
+        for len in [0, 1, 2, 10, 100, 1000] {
+            let vec: Vec<String> = (0..len)
+                .map(|_| (random(&mut seed) % 50).to_string())
+                .collect();
+
+            let idx = argsort(&vec);
+            let mut sorted = vec.clone();
+            apply_permutation(&mut sorted, &idx);
+
+            let mut expected = vec.clone();
+            expected.sort();
+            assert_eq!(sorted, expected);
+
+            // 逆置换把数组还原
+            apply_permutation(&mut sorted, &inverse_permutation(&idx));
+            assert_eq!(sorted, vec);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not a permutation")]
+    fn test_repeated_index() {
+        apply_permutation(&mut [1, 2, 3], &[0, 2, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a permutation")]
+    fn test_out_of_range() {
+        inverse_permutation(&[0, 3, 1]);
+    }
+}
@@ -0,0 +1,79 @@
+/// 排序过程中的探针。
+///
+/// 各个排序算法在比较、交换、移动元素、递归和分配辅助空间时调用对应的方法，
+/// 默认实现什么也不做。普通的排序函数传入 `&()`，编译后探针的调用都会被优化掉。
+///
+/// 下标都相对于当前处理的切片；对子切片调用其他排序函数时，前后用
+/// [`shift`](Probe::shift) 和 [`unshift`](Probe::unshift) 标出子切片的起点。
+pub(crate) trait Probe {
+    /// 比较了一次元素
+    fn compare(&self) {}
+
+    /// 比较了 `vec[i]` 与 `vec[j]`。
+    ///
+    /// 只有需要下标的探针才用到，比较次数由 [`compare`](Probe::compare) 统计。
+    fn compare_at(&self, _i: usize, _j: usize) {}
+
+    /// 交换了 `vec[i]` 与 `vec[j]`
+    fn swap(&self, _i: usize, _j: usize) {}
+
+    /// `swap` 为真时交换了 `vec[i]` 与 `vec[j]`，否则两者都没有动。
+    ///
+    /// 供无分支的代码使用，由探针决定怎样记录，调用方不需要根据 `swap` 分支。
+    fn swap_if(&self, i: usize, j: usize, swap: bool) {
+        if swap {
+            self.swap(i, j);
+        }
+    }
+
+    /// 移动了 `n` 个元素，不含交换
+    fn moves(&self, _n: usize) {}
+
+    /// 把 `vec[from]` 插入到 `to`（`to < from`），中间的元素右移一位
+    fn insert(&self, from: usize, to: usize) {
+        self.moves(from - to + 1);
+    }
+
+    /// 开始合并 `vec[start..mid]` 与 `vec[mid..end]`
+    fn merge_range(&self, _start: usize, _mid: usize, _end: usize) {}
+
+    /// 合并时，把合并开始时位于 `from` 的元素写到 `to`
+    fn write(&self, _to: usize, _from: usize) {
+        self.moves(1);
+    }
+
+    /// 开始对 `vec[start..end]` 分区，选中 `vec[pivot]` 作为分区点
+    fn partition_start(&self, _start: usize, _end: usize, _pivot: usize) {}
+
+    /// 之后的下标相对于 `start`，即进入了子切片 `vec[start..]`
+    fn shift(&self, _start: usize) {}
+
+    /// 从子切片 `vec[start..]` 返回
+    fn unshift(&self, _start: usize) {}
+
+    /// 进入下一层递归
+    fn enter(&self) {}
+
+    /// 从下一层递归返回
+    fn leave(&self) {}
+
+    /// 分配了能放下 `n` 个元素（或下标、计数）的辅助空间
+    fn alloc(&self, _n: usize) {}
+}
+
+/// 可以在线程之间拆分的探针，供并行排序使用。
+pub(crate) trait Fork: Probe + Sized {
+    /// 为新线程创建一个探针，从当前的递归深度开始统计
+    fn fork(&self) -> Self;
+
+    /// 线程结束后，把它的探针汇总回来
+    fn join(&self, child: Self);
+}
+
+impl Probe for () {}
+
+impl Fork for () {
+    fn fork(&self) -> Self {}
+
+    fn join(&self, _child: Self) {}
+}
@@ -0,0 +1,671 @@
+use super::{Probe, INSERTION_LEN};
+use std::cmp::Ordering;
+
+/// 每一层的桶数：键已结束的元素占 0 号桶，字节 `b` 占 `b + 1` 号桶
+const BUCKETS: usize = 257;
+
+/// 计数排序的取值范围超过元素个数的该倍数时改用 LSD 基数排序
+const COUNT_FACTOR: usize = 8;
+
+/// # 基数排序的键
+///
+/// 把键看作一串字节，从最高位（第 0 个字节）开始逐字节比较，
+/// 字节串是另一个的前缀时，较短的那个更小，与字典序一致。
+///
+/// 已为所有整数类型、`str`、`String`、`[u8]`、`Vec<u8>` 实现。
+/// 有符号整数会翻转符号位，使负数排在正数之前。
+///
+/// ```
+/// use algorithm_study::sorting::{radix_sort_lsd, RadixKey};
+///
+/// // 按版本号排序：主版本号在高位，次版本号在低位
+/// #[derive(Debug, PartialEq)]
+/// struct Version(u16, u16);
+///
+/// impl RadixKey for Version {
+///     fn key_len(&self) -> usize {
+///         4
+///     }
+///
+///     fn key_byte(&self, level: usize) -> Option<u8> {
+///         let bytes = [self.0.to_be_bytes(), self.1.to_be_bytes()].concat();
+///         bytes.get(level).copied()
+///     }
+/// }
+///
+/// let mut vec = vec![Version(1, 10), Version(0, 3), Version(1, 2)];
+/// radix_sort_lsd(&mut vec);
+///
+/// assert_eq!(vec, vec![Version(0, 3), Version(1, 2), Version(1, 10)]);
+/// ```
+///
+pub trait RadixKey {
+    /// 键的字节数。
+    fn key_len(&self) -> usize;
+
+    /// 键的第 `level` 个字节，`level` 超出键的长度时返回 `None`。
+    fn key_byte(&self, level: usize) -> Option<u8>;
+}
+
+macro_rules! impl_radix_key_unsigned {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            fn key_len(&self) -> usize {
+                std::mem::size_of::<$t>()
+            }
+
+            fn key_byte(&self, level: usize) -> Option<u8> {
+                self.to_be_bytes().get(level).copied()
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_radix_key_signed {
+    ($($t:ty => $u:ty),*) => {$(
+        impl RadixKey for $t {
+            fn key_len(&self) -> usize {
+                std::mem::size_of::<$t>()
+            }
+
+            fn key_byte(&self, level: usize) -> Option<u8> {
+                // 翻转符号位：负数的最高位变成 0，排在正数之前
+                let flipped = (*self as $u) ^ (1 << (<$u>::BITS - 1));
+                flipped.to_be_bytes().get(level).copied()
+            }
+        }
+    )*};
+}
+
+impl_radix_key_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+impl RadixKey for [u8] {
+    fn key_len(&self) -> usize {
+        self.len()
+    }
+
+    fn key_byte(&self, level: usize) -> Option<u8> {
+        self.get(level).copied()
+    }
+}
+
+impl RadixKey for Vec<u8> {
+    fn key_len(&self) -> usize {
+        self.len()
+    }
+
+    fn key_byte(&self, level: usize) -> Option<u8> {
+        self.get(level).copied()
+    }
+}
+
+impl RadixKey for str {
+    fn key_len(&self) -> usize {
+        self.len()
+    }
+
+    fn key_byte(&self, level: usize) -> Option<u8> {
+        self.as_bytes().get(level).copied()
+    }
+}
+
+impl RadixKey for String {
+    fn key_len(&self) -> usize {
+        self.len()
+    }
+
+    fn key_byte(&self, level: usize) -> Option<u8> {
+        self.as_bytes().get(level).copied()
+    }
+}
+
+impl<T: RadixKey + ?Sized> RadixKey for &T {
+    fn key_len(&self) -> usize {
+        (**self).key_len()
+    }
+
+    fn key_byte(&self, level: usize) -> Option<u8> {
+        (**self).key_byte(level)
+    }
+}
+
+/// 元素在第 `level` 层所属的桶
+fn bucket<K: RadixKey + ?Sized>(key: &K, level: usize) -> usize {
+    key.key_byte(level).map_or(0, |b| b as usize + 1)
+}
+
+/// # 计数排序
+///
+/// 统计每个值出现的次数，再按值从小到大依次排列，时间复杂度 O(n + k)，
+/// k 是最大值与最小值之差。只适合取值范围较小的整数，内存占用与 k 成正比。
+///
+/// k 超过元素个数的 8 倍时，计数表大部分是空的，改用 [`radix_sort_lsd`]，
+/// 内存占用不会超过 O(n)。
+///
+/// ```
+/// # use algorithm_study::sorting::counting_sort;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![3,-5,2,-4,1,3];
+/// let mut vec_2: Vec<u64> = vec![1_000_003, 1_000_001, 1_000_002];
+///
+/// counting_sort(&mut vec_0);
+/// counting_sort(&mut vec_1);
+/// counting_sort(&mut vec_2);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![-5,-4,1,2,3,3]);
+/// assert_eq!(vec_2, vec![1_000_001, 1_000_002, 1_000_003]);
+/// ```
+///
+pub fn counting_sort<T>(vec: &mut [T])
+where
+    T: Copy + Into<i128>,
+{
+    counting_sort_impl(vec, &());
+}
+
+/// # 计数排序（按键排序）
+///
+/// 按 `f` 提取的非负整数键做计数排序，排序是稳定的。
+/// 内存占用与最大的键成正比，最大的键远大于元素个数时同样改用 [`radix_sort_lsd`]。
+///
+/// ```
+/// # use algorithm_study::sorting::counting_sort_by_key;
+/// #
+/// let mut vec = vec![("b", 1), ("a", 0), ("c", 1), ("d", 0)];
+///
+/// counting_sort_by_key(&mut vec, |x| x.1);
+///
+/// assert_eq!(vec, vec![("a", 0), ("d", 0), ("b", 1), ("c", 1)]);
+/// ```
+///
+pub fn counting_sort_by_key<T, F>(vec: &mut [T], f: F)
+where
+    F: FnMut(&T) -> usize,
+{
+    counting_sort_by_key_impl(vec, f, &());
+}
+
+pub(super) fn counting_sort_impl<T, P>(vec: &mut [T], probe: &P)
+where
+    T: Copy + Into<i128>,
+    P: Probe,
+{
+    let (min, max) = match (
+        vec.iter().map(|&x| x.into()).min(),
+        vec.iter().map(|&x| x.into()).max(),
+    ) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return,
+    };
+
+    // 与最小值的差在 [0, 2^128) 内，按 u128 回绕相减不会溢出
+    let offset = |&x: &T| (x.into() as u128).wrapping_sub(min as u128);
+
+    // 差放不进 usize 时取值范围一定很大，直接用 LSD 基数排序
+    if usize::try_from((max as u128).wrapping_sub(min as u128)).is_err() {
+        let keys: Vec<u128> = vec.iter().map(offset).collect();
+        probe.alloc(keys.len());
+        let mut dest = lsd_order(&keys, probe);
+        scatter(vec, &mut dest, probe);
+        return;
+    }
+
+    counting_sort_by_key_impl(vec, |x| offset(x) as usize, probe);
+}
+
+fn counting_sort_by_key_impl<T, F, P>(vec: &mut [T], mut f: F, probe: &P)
+where
+    F: FnMut(&T) -> usize,
+    P: Probe,
+{
+    let keys: Vec<usize> = vec.iter().map(&mut f).collect();
+    probe.alloc(keys.len());
+    let max = match keys.iter().max() {
+        Some(&max) => max,
+        None => return,
+    };
+
+    // 取值范围远大于元素个数时，计数表大部分是空的，改用 LSD 基数排序，同样是稳定的
+    let range = match max.checked_add(1) {
+        Some(range) if range <= keys.len().saturating_mul(COUNT_FACTOR).max(BUCKETS) => range,
+        _ => {
+            let mut dest = lsd_order(&keys, probe);
+            scatter(vec, &mut dest, probe);
+            return;
+        }
+    };
+
+    // 统计每个键出现的次数
+    let mut counts = vec![0; range];
+    probe.alloc(range);
+    for &key in &keys {
+        counts[key] += 1;
+    }
+
+    // 前缀和：每个键的第一个元素应放的位置
+    let mut pos = 0;
+    for count in counts.iter_mut() {
+        let n = *count;
+        *count = pos;
+        pos += n;
+    }
+
+    // 按原顺序依次放入，相同的键保持相对顺序
+    let mut dest: Vec<usize> = keys
+        .iter()
+        .map(|&key| {
+            counts[key] += 1;
+            counts[key] - 1
+        })
+        .collect();
+    probe.alloc(dest.len());
+
+    scatter(vec, &mut dest, probe);
+}
+
+/// # LSD 基数排序
+///
+/// 从最低位的字节开始，每一轮按当前字节做一次稳定的计数排序，
+/// 所有字节都排过之后，整体就按键有序了。时间复杂度 O(w·n)，w 是键的字节数。
+///
+/// 排序是稳定的。较短的键视为在末尾补上了比任何字节都小的值，
+/// 因此也可以用于字符串。
+///
+/// ```
+/// # use algorithm_study::sorting::radix_sort_lsd;
+/// #
+/// let mut vec_0: Vec<u32> = Vec::new();
+/// let mut vec_1 = vec![3_000_000_000u32, 7, 65_536, 0, 255];
+/// let mut vec_2 = vec![i64::MAX, -1, i64::MIN, 0, 42];
+/// let mut vec_3 = vec!["banana", "apple", "app", "cherry"];
+///
+/// radix_sort_lsd(&mut vec_0);
+/// radix_sort_lsd(&mut vec_1);
+/// radix_sort_lsd(&mut vec_2);
+/// radix_sort_lsd(&mut vec_3);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![0, 7, 255, 65_536, 3_000_000_000]);
+/// assert_eq!(vec_2, vec![i64::MIN, -1, 0, 42, i64::MAX]);
+/// assert_eq!(vec_3, vec!["app", "apple", "banana", "cherry"]);
+/// ```
+///
+pub fn radix_sort_lsd<T>(vec: &mut [T])
+where
+    T: RadixKey,
+{
+    radix_sort_lsd_impl(vec, &());
+}
+
+/// # LSD 基数排序（按键排序）
+///
+/// 与 [`radix_sort_lsd`] 相同，但按 `f` 提取的键排序，每个元素只提取一次键。
+///
+/// ```
+/// # use algorithm_study::sorting::radix_sort_lsd_by_key;
+/// #
+/// let mut vec = vec![("b", 300u32), ("a", 7), ("c", 300), ("d", 7)];
+///
+/// radix_sort_lsd_by_key(&mut vec, |x| x.1);
+///
+/// assert_eq!(vec, vec![("a", 7), ("d", 7), ("b", 300), ("c", 300)]);
+/// ```
+///
+pub fn radix_sort_lsd_by_key<T, K, F>(vec: &mut [T], f: F)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    let keys: Vec<K> = vec.iter().map(f).collect();
+    let mut dest = lsd_order(&keys, &());
+    scatter(vec, &mut dest, &());
+}
+
+pub(super) fn radix_sort_lsd_impl<T, P>(vec: &mut [T], probe: &P)
+where
+    T: RadixKey,
+    P: Probe,
+{
+    let mut dest = lsd_order(vec, probe);
+    scatter(vec, &mut dest, probe);
+}
+
+/// 对键做 LSD 基数排序，返回每个元素排序后应在的位置。
+///
+/// 只在下标上做计数排序，元素本身最后一次性移动到位。
+fn lsd_order<K: RadixKey, P: Probe>(keys: &[K], probe: &P) -> Vec<usize> {
+    let levels = keys.iter().map(|k| k.key_len()).max().unwrap_or(0);
+
+    // order[i] 是当前第 i 小的元素的原下标
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    let mut next = vec![0; keys.len()];
+    probe.alloc(order.len());
+    probe.alloc(next.len());
+
+    for level in (0..levels).rev() {
+        let mut counts = [0; BUCKETS];
+        for &i in &order {
+            counts[bucket(&keys[i], level)] += 1;
+        }
+
+        // 所有元素都在同一个桶里，这一轮不改变顺序
+        if counts.contains(&keys.len()) {
+            continue;
+        }
+
+        let mut pos = 0;
+        for count in counts.iter_mut() {
+            let n = *count;
+            *count = pos;
+            pos += n;
+        }
+
+        for &i in &order {
+            let b = bucket(&keys[i], level);
+            next[counts[b]] = i;
+            counts[b] += 1;
+        }
+
+        std::mem::swap(&mut order, &mut next);
+    }
+
+    // 转换成每个元素的目标位置
+    let mut dest = vec![0; keys.len()];
+    probe.alloc(dest.len());
+    for (pos, &i) in order.iter().enumerate() {
+        dest[i] = pos;
+    }
+    dest
+}
+
+/// 按 `dest` 把 `vec[i]` 移动到 `dest[i]`，沿着置换的环依次交换。
+///
+/// 完成后 `dest` 变为恒等置换。
+fn scatter<T, P: Probe>(vec: &mut [T], dest: &mut [usize], probe: &P) {
+    for i in 0..vec.len() {
+        while dest[i] != i {
+            let j = dest[i];
+            vec.swap(i, j);
+            probe.swap(i, j);
+            dest.swap(i, j);
+        }
+    }
+}
+
+/// # MSD 基数排序
+///
+/// 从最高位的字节开始，按当前字节把元素原地分到 257 个桶里（美国国旗排序），
+/// 再对每个桶按下一个字节递归，短区间改用插入排序。
+///
+/// 只移动键还没有确定顺序的那部分，字符串共同前缀较短时比 LSD 更快。
+/// 原地排序，不稳定。
+///
+/// ```
+/// # use algorithm_study::sorting::radix_sort_msd;
+/// #
+/// let mut vec_0: Vec<String> = Vec::new();
+/// let mut vec_1: Vec<String> = ["she", "sells", "seashells", "by", "the", "sea", "shore"]
+///     .iter()
+///     .map(|s| s.to_string())
+///     .collect();
+/// let mut vec_2: Vec<&[u8]> = vec![b"\xff", b"", b"\x00\x01", b"\x00"];
+/// let mut vec_3 = vec![-3i32, 100, -100, 3, 0];
+///
+/// radix_sort_msd(&mut vec_0);
+/// radix_sort_msd(&mut vec_1);
+/// radix_sort_msd(&mut vec_2);
+/// radix_sort_msd(&mut vec_3);
+///
+/// assert_eq!(vec_0, Vec::<String>::new());
+/// assert_eq!(vec_1, vec!["by", "sea", "seashells", "sells", "she", "shore", "the"]);
+/// assert_eq!(vec_2, vec![&b""[..], b"\x00", b"\x00\x01", b"\xff"]);
+/// assert_eq!(vec_3, vec![-100, -3, 0, 3, 100]);
+/// ```
+///
+pub fn radix_sort_msd<T>(vec: &mut [T])
+where
+    T: RadixKey,
+{
+    radix_sort_msd_impl(vec, &());
+}
+
+pub(super) fn radix_sort_msd_impl<T, P>(vec: &mut [T], probe: &P)
+where
+    T: RadixKey,
+    P: Probe,
+{
+    radix_sort_msd_with_level(vec, 0, probe);
+
+    fn radix_sort_msd_with_level<T: RadixKey, P: Probe>(
+        mut vec: &mut [T],
+        mut level: usize,
+        probe: &P,
+    ) {
+        loop {
+            if vec.len() <= INSERTION_LEN {
+                let is_less = &mut |a: &T, b: &T| {
+                    probe.compare();
+                    key_cmp(a, b, level) == Ordering::Less
+                };
+                super::insertion_sort_impl(vec, is_less, probe);
+                return;
+            }
+
+            let mut counts = [0; BUCKETS];
+            for key in vec.iter() {
+                counts[bucket(key, level)] += 1;
+            }
+
+            // 每个桶的起止位置
+            let mut starts = [0; BUCKETS];
+            let mut ends = [0; BUCKETS];
+            let mut pos = 0;
+            for b in 0..BUCKETS {
+                starts[b] = pos;
+                pos += counts[b];
+                ends[b] = pos;
+            }
+
+            // 原地分桶：把每个位置上的元素交换到它所属的桶，直到该位置放入了本桶的元素
+            let mut next = starts;
+            for b in 0..BUCKETS {
+                while next[b] < ends[b] {
+                    let target = bucket(&vec[next[b]], level);
+                    if target == b {
+                        next[b] += 1;
+                    } else {
+                        vec.swap(next[b], next[target]);
+                        probe.swap(next[b], next[target]);
+                        next[target] += 1;
+                    }
+                }
+            }
+
+            // 0 号桶的键已经结束，全部相等，不用再排。
+            // 最大的桶留在循环中处理下一个字节，只对其余的桶递归：它们都不超过区间的一半，
+            // 递归深度不超过 log2(n)，与键的长度无关
+            let largest = (1..BUCKETS).max_by_key(|&b| counts[b]).unwrap();
+            for b in 1..BUCKETS {
+                if b != largest && counts[b] > 1 {
+                    probe.enter();
+                    radix_sort_msd_with_level(&mut vec[starts[b]..ends[b]], level + 1, probe);
+                    probe.leave();
+                }
+            }
+
+            if counts[largest] <= 1 {
+                return;
+            }
+            vec = &mut vec[starts[largest]..ends[largest]];
+            level += 1;
+        }
+    }
+}
+
+/// 从第 `level` 个字节开始按字典序比较两个键
+fn key_cmp<K: RadixKey + ?Sized>(a: &K, b: &K, mut level: usize) -> Ordering {
+    loop {
+        match (a.key_byte(level), b.key_byte(level)) {
+            (None, None) => return Ordering::Equal,
+            (x, y) if x != y => return x.cmp(&y),
+            _ => level += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::random;
+
+    #[test]
+    fn test_signed_keys() {
+        let mut vec = vec![0i8, -1, i8::MIN, i8::MAX, 1, -128, 127];
+        radix_sort_lsd(&mut vec);
+        assert_eq!(vec![-128, -128, -1, 0, 1, 127, 127], vec);
+
+        let mut vec = vec![0i64, -1, i64::MIN, i64::MAX, 1 << 40, -(1 << 40)];
+        radix_sort_msd(&mut vec);
+        assert_eq!(vec![i64::MIN, -(1 << 40), -1, 0, 1 << 40, i64::MAX], vec);
+    }
+
+    #[test]
+    fn test_random_integers() {
+        let mut seed = 7;
+
+        for len in [0, 1, 2, 15, 16, 17, 100, 5000] {
+            let vec: Vec<u64> = (0..len).map(|_| random(&mut seed)).collect();
+            let mut expected = vec.clone();
+            expected.sort();
+
+            let mut sorted = vec.clone();
+            radix_sort_lsd(&mut sorted);
+            assert_eq!(expected, sorted);
+
+            let mut sorted = vec.clone();
+            radix_sort_msd(&mut sorted);
+            assert_eq!(expected, sorted);
+
+            let vec: Vec<i32> = vec.iter().map(|&x| x as i32).collect();
+            let mut expected = vec.clone();
+            expected.sort();
+
+            let mut sorted = vec.clone();
+            radix_sort_lsd(&mut sorted);
+            assert_eq!(expected, sorted);
+
+            let mut sorted = vec.clone();
+            radix_sort_msd(&mut sorted);
+            assert_eq!(expected, sorted);
+
+            let vec: Vec<i16> = vec.iter().map(|&x| x as i16 % 300).collect();
+            let mut expected = vec.clone();
+            expected.sort();
+
+            let mut sorted = vec.clone();
+            counting_sort(&mut sorted);
+            assert_eq!(expected, sorted);
+        }
+    }
+
+    #[test]
+    fn test_random_strings() {
+        let mut seed = 11;
+
+        let vec: Vec<String> = (0..2000)
+            .map(|_| {
+                let len = random(&mut seed) % 6;
+                (0..len)
+                    .map(|_| (b'a' + (random(&mut seed) % 3) as u8) as char)
+                    .collect()
+            })
+            .collect();
+        let mut expected = vec.clone();
+        expected.sort();
+
+        let mut sorted = vec.clone();
+        radix_sort_lsd(&mut sorted);
+        assert_eq!(expected, sorted);
+
+        let mut sorted = vec.clone();
+        radix_sort_msd(&mut sorted);
+        assert_eq!(expected, sorted);
+    }
+
+    #[test]
+    fn test_stability() {
+        let mut seed = 3;
+        let vec: Vec<(u16, usize)> = (0..3000)
+            .map(|i| ((random(&mut seed) % 50) as u16, i))
+            .collect();
+        let mut expected = vec.clone();
+        expected.sort_by_key(|x| x.0);
+
+        let mut sorted = vec.clone();
+        radix_sort_lsd_by_key(&mut sorted, |x| x.0);
+        assert_eq!(expected, sorted);
+
+        let mut sorted = vec.clone();
+        counting_sort_by_key(&mut sorted, |x| x.0 as usize);
+        assert_eq!(expected, sorted);
+    }
+
+    #[test]
+    fn test_long_prefix() {
+        // 共同前缀很长时，MSD 在最大的桶上循环，递归深度与键的长度无关
+        let prefix = "a".repeat(20_000);
+        let mut vec = vec![prefix.clone(); 20];
+        radix_sort_msd(&mut vec);
+        assert!(vec.iter().all(|s| *s == prefix));
+
+        let mut seed = 5;
+        let vec: Vec<String> = (0..200)
+            .map(|_| {
+                let len = random(&mut seed) as usize % 20_000;
+                let tail = (b'a' + (random(&mut seed) % 3) as u8) as char;
+                format!("{}{}", &prefix[..len], tail)
+            })
+            .collect();
+        let mut expected = vec.clone();
+        expected.sort();
+
+        let mut sorted = vec;
+        radix_sort_msd(&mut sorted);
+        assert_eq!(expected, sorted);
+    }
+
+    #[test]
+    fn test_sparse_keys() {
+        // 取值范围很大时不按取值范围分配计数表
+        let mut vec = vec![u32::MAX, 0, 7, u32::MAX];
+        counting_sort(&mut vec);
+        assert_eq!(vec![0, 7, u32::MAX, u32::MAX], vec);
+
+        let mut vec = vec![i64::MAX, i64::MIN, 0];
+        counting_sort(&mut vec);
+        assert_eq!(vec![i64::MIN, 0, i64::MAX], vec);
+
+        // 差超出 usize 的范围时同样改用 LSD 基数排序
+        let mut vec = vec![i128::MAX, 0, i128::MIN, -1, i128::MAX];
+        counting_sort(&mut vec);
+        assert_eq!(vec![i128::MIN, -1, 0, i128::MAX, i128::MAX], vec);
+
+        let mut vec = vec![1i128 << 100, 0, -(1i128 << 100)];
+        counting_sort(&mut vec);
+        assert_eq!(vec![-(1i128 << 100), 0, 1i128 << 100], vec);
+
+        let mut vec = vec![u64::MAX, 1, u64::MAX - 1, 0];
+        counting_sort(&mut vec);
+        assert_eq!(vec![0, 1, u64::MAX - 1, u64::MAX], vec);
+
+        let mut vec = vec![(usize::MAX, 'a'), (0, 'b'), (usize::MAX, 'c'), (0, 'd')];
+        counting_sort_by_key(&mut vec, |x| x.0);
+        assert_eq!(
+            vec![(0, 'b'), (0, 'd'), (usize::MAX, 'a'), (usize::MAX, 'c')],
+            vec
+        );
+    }
+}
@@ -0,0 +1,434 @@
+use super::{
+    depth_limit, heap, insertion_sort_impl, partition, partition_at, quick_sort_impl, INSERTION_LEN,
+};
+use std::cmp::Ordering;
+use std::vec;
+
+/// # 快速选择
+///
+/// 重新排列 `vec`，使 `vec[n]` 恰好是排序后位于 `n` 的元素，
+/// 它左侧的元素都不大于它，右侧的元素都不小于它，返回 `vec[n]` 的可变引用。
+///
+/// 与 [`quick_sort`](super::quick_sort) 使用同样的分区方式，但每轮只处理 `n` 所在的一侧，
+/// 平均时间复杂度为 O(n)。分区层数超过 `2·log2(n)` 时改用中位数的中位数选取 pivot，
+/// 最坏也是 O(n)。不稳定。
+///
+/// `n >= vec.len()` 时 panic。
+///
+/// ```
+/// # use algorithm_study::sorting::select_nth;
+/// #
+/// let mut vec = vec![9,1,8,2,7,3,6,4,5];
+///
+/// let median = *select_nth(&mut vec, 4);
+///
+/// assert_eq!(median, 5);
+/// assert!(vec[..4].iter().all(|&x| x <= 5));
+/// assert!(vec[5..].iter().all(|&x| x >= 5));
+/// ```
+///
+pub fn select_nth<T>(vec: &mut [T], n: usize) -> &mut T
+where
+    T: Ord,
+{
+    select_nth_impl(vec, n, &mut |a: &T, b: &T| a < b);
+    &mut vec[n]
+}
+
+/// # 快速选择（自定义比较）
+///
+/// 与 [`select_nth`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::select_nth_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// assert_eq!(*select_nth_by(&mut vec, 0, |a, b| b.cmp(a)), 5);
+/// ```
+///
+pub fn select_nth_by<T, F>(vec: &mut [T], n: usize, mut compare: F) -> &mut T
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    select_nth_impl(vec, n, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less);
+    &mut vec[n]
+}
+
+/// # 快速选择（按键排序）
+///
+/// 与 [`select_nth`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::select_nth_by_key;
+/// #
+/// let mut vec = vec![("b", 2), ("c", 3), ("a", 1)];
+///
+/// assert_eq!(*select_nth_by_key(&mut vec, 1, |x| x.1), ("b", 2));
+/// ```
+///
+pub fn select_nth_by_key<T, K, F>(vec: &mut [T], n: usize, mut f: F) -> &mut T
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    select_nth_impl(vec, n, &mut |a: &T, b: &T| f(a) < f(b));
+    &mut vec[n]
+}
+
+fn select_nth_impl<T, F>(vec: &mut [T], n: usize, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let len = vec.len();
+    assert!(
+        n < len,
+        "index {} out of range for slice of length {}",
+        n,
+        len
+    );
+
+    let (mut start, mut end) = (0, len);
+    let mut limit = depth_limit(len);
+
+    while end - start > INSERTION_LEN {
+        // pivot 选得太差，剩余部分改用线性时间的选择
+        if limit == 0 {
+            select_linear(vec, start, end, n, is_less);
+            return;
+        }
+        limit -= 1;
+
+        let mid = partition(vec, start, end, is_less, &());
+
+        // 只在 n 所在的一侧继续
+        match n.cmp(&mid) {
+            Ordering::Less => end = mid,
+            Ordering::Greater => start = mid + 1,
+            Ordering::Equal => return,
+        }
+    }
+
+    insertion_sort_impl(&mut vec[start..end], is_less, &());
+}
+
+/// 用中位数的中位数作为 pivot，在 `vec[start..end]` 中选出第 `n` 个元素，最坏 O(n)。
+fn select_linear<T, F>(vec: &mut [T], mut start: usize, mut end: usize, n: usize, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    while end - start > 5 {
+        let pivot = median_of_medians(vec, start, end, is_less);
+        let mid = partition_at(vec, start, end, pivot, is_less, &());
+
+        match n.cmp(&mid) {
+            Ordering::Less => end = mid,
+            Ordering::Greater => start = mid + 1,
+            Ordering::Equal => return,
+        }
+    }
+
+    insertion_sort_impl(&mut vec[start..end], is_less, &());
+}
+
+/// 返回 `vec[start..end]` 中一个近似中位数的下标。
+///
+/// 每 5 个元素一组，各组的中位数依次换到区间开头，再递归选出它们的中位数。
+/// 至少有 3/10 的元素不大于它，也至少有 3/10 的元素不小于它。
+fn median_of_medians<T, F>(vec: &mut [T], start: usize, end: usize, is_less: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let groups = (end - start) / 5;
+
+    for g in 0..groups {
+        let s = start + 5 * g;
+        insertion_sort_impl(&mut vec[s..s + 5], is_less, &());
+        vec.swap(start + g, s + 2);
+    }
+
+    let mid = start + groups / 2;
+    select_linear(vec, start, start + groups, mid, is_less);
+    mid
+}
+
+/// # 部分排序
+///
+/// 把最小的 `k` 个元素按升序排在 `vec` 的最前面，其余元素的顺序不确定。
+/// 先用 [`select_nth`] 把这 `k` 个元素挑到前面，再只对它们排序，时间复杂度 O(n + k log k)。
+///
+/// `k` 大于数组长度时，对整个数组排序。不稳定。
+///
+/// ```
+/// # use algorithm_study::sorting::partial_sort;
+/// #
+/// let mut vec = vec![9,1,8,2,7,3,6,4,5];
+///
+/// partial_sort(&mut vec, 3);
+///
+/// assert_eq!(vec[..3], [1,2,3]);
+/// ```
+///
+pub fn partial_sort<T>(vec: &mut [T], k: usize)
+where
+    T: Ord,
+{
+    partial_sort_impl(vec, k, &mut |a: &T, b: &T| a < b);
+}
+
+/// # 部分排序（自定义比较）
+///
+/// 与 [`partial_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::partial_sort_by;
+/// #
+/// let mut vec = vec![9,1,8,2,7,3,6,4,5];
+///
+/// partial_sort_by(&mut vec, 2, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec[..2], [9,8]);
+/// ```
+///
+pub fn partial_sort_by<T, F>(vec: &mut [T], k: usize, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    partial_sort_impl(vec, k, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less);
+}
+
+/// # 部分排序（按键排序）
+///
+/// 与 [`partial_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::partial_sort_by_key;
+/// #
+/// let mut vec = vec![("c", 3), ("a", 1), ("d", 4), ("b", 2)];
+///
+/// partial_sort_by_key(&mut vec, 2, |x| x.1);
+///
+/// assert_eq!(vec[..2], [("a", 1), ("b", 2)]);
+/// ```
+///
+pub fn partial_sort_by_key<T, K, F>(vec: &mut [T], k: usize, mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    partial_sort_impl(vec, k, &mut |a: &T, b: &T| f(a) < f(b));
+}
+
+fn partial_sort_impl<T, F>(vec: &mut [T], k: usize, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let k = k.min(vec.len());
+    if k == 0 {
+        return;
+    }
+
+    // 选出第 k 小的元素后，它左侧就是更小的 k - 1 个元素
+    if k < vec.len() {
+        select_nth_impl(vec, k - 1, is_less);
+    }
+    quick_sort_impl(&mut vec[..k], is_less, &());
+}
+
+/// # 前 k 大的元素
+///
+/// 遍历 `iter`，用容量为 `k` 的小根堆保留目前最大的 `k` 个元素：
+/// 新元素比堆顶大时替换堆顶并下沉，否则丢弃。最后按降序返回它们的迭代器。
+///
+/// 只占用 O(k) 的空间，时间复杂度 O(n log k)，适合从很长的序列里取少量最大值。
+/// 相等的元素中保留哪一个是不确定的。
+///
+/// ```
+/// # use algorithm_study::sorting::top_k;
+/// #
+/// let top: Vec<i32> = top_k(vec![3,9,1,8,2,7], 3).collect();
+///
+/// assert_eq!(top, vec![9,8,7]);
+/// assert_eq!(top_k(0..1_000_000, 2).collect::<Vec<_>>(), vec![999_999, 999_998]);
+/// assert_eq!(top_k(vec![1,2], 5).collect::<Vec<_>>(), vec![2,1]);
+/// ```
+///
+pub fn top_k<I>(iter: I, k: usize) -> vec::IntoIter<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Ord,
+{
+    top_k_impl(iter, k, &mut |a: &I::Item, b: &I::Item| a < b)
+}
+
+/// # 前 k 大的元素（自定义比较）
+///
+/// 与 [`top_k`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::top_k_by;
+/// #
+/// // 反过来比较，即取最小的 k 个
+/// let top: Vec<i32> = top_k_by(vec![3,9,1,8,2,7], 2, |a, b| b.cmp(a)).collect();
+///
+/// assert_eq!(top, vec![1,2]);
+/// ```
+///
+pub fn top_k_by<I, F>(iter: I, k: usize, mut compare: F) -> vec::IntoIter<I::Item>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    top_k_impl(iter, k, &mut |a: &I::Item, b: &I::Item| {
+        compare(a, b) == Ordering::Less
+    })
+}
+
+/// # 前 k 大的元素（按键排序）
+///
+/// 与 [`top_k`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::top_k_by_key;
+/// #
+/// let words = vec!["a", "abcd", "ab", "abc"];
+///
+/// let top: Vec<&str> = top_k_by_key(words, 2, |s| s.len()).collect();
+///
+/// assert_eq!(top, vec!["abcd", "abc"]);
+/// ```
+///
+pub fn top_k_by_key<I, K, F>(iter: I, k: usize, mut f: F) -> vec::IntoIter<I::Item>
+where
+    I: IntoIterator,
+    K: Ord,
+    F: FnMut(&I::Item) -> K,
+{
+    top_k_impl(iter, k, &mut |a: &I::Item, b: &I::Item| f(a) < f(b))
+}
+
+fn top_k_impl<I, F>(iter: I, k: usize, is_less: &mut F) -> vec::IntoIter<I::Item>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item, &I::Item) -> bool,
+{
+    // 反过来比较，堆顶就是保留的元素中最小的
+    let is_greater = &mut |a: &I::Item, b: &I::Item| is_less(b, a);
+
+    // k 可能远大于元素个数，按迭代器的长度下界预留空间，不足时再增长
+    let iter = iter.into_iter();
+    let mut heap = Vec::with_capacity(k.min(iter.size_hint().0));
+
+    if k > 0 {
+        for item in iter {
+            if heap.len() < k {
+                heap.push(item);
+                let last = heap.len() - 1;
+                heap::sift_up(&mut heap, last, is_greater, &());
+            } else if is_greater(&item, &heap[0]) {
+                heap[0] = item;
+                heap::sift_down(&mut heap, 0, is_greater, &());
+            }
+        }
+    }
+
+    // 小根堆做堆排序，得到降序
+    heap::sort_heap(&mut heap, is_greater, &());
+    heap.into_iter()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::random;
+
+    #[test]
+    fn test_select_nth() {
+        let mut seed = 21;
+
+        for len in [1, 2, 5, 17, 100, 1000] {
+            for range in [2, 10, u64::MAX] {
+                let vec: Vec<u64> = (0..len).map(|_| random(&mut seed) % range).collect();
+                let mut expected = vec.clone();
+                expected.sort();
+
+                for n in [0, len as usize / 3, len as usize - 1] {
+                    let mut selected = vec.clone();
+                    assert_eq!(expected[n], *select_nth(&mut selected, n));
+                    assert!(selected[..n].iter().all(|x| *x <= expected[n]));
+                    assert!(selected[n + 1..].iter().all(|x| *x >= expected[n]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_linear() {
+        // 直接检验中位数的中位数这条路径
+        let mut seed = 23;
+
+        for len in [6, 37, 500, 4321] {
+            let vec: Vec<u64> = (0..len).map(|_| random(&mut seed) % 50).collect();
+            let mut expected = vec.clone();
+            expected.sort();
+
+            for n in [0, len / 2, len - 1] {
+                let mut selected = vec.clone();
+                select_linear(&mut selected, 0, len, n, &mut |a: &u64, b: &u64| a < b);
+                assert_eq!(expected[n], selected[n]);
+                assert!(selected[..n].iter().all(|x| *x <= expected[n]));
+                assert!(selected[n + 1..].iter().all(|x| *x >= expected[n]));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_select_out_of_range() {
+        select_nth(&mut [1, 2, 3], 3);
+    }
+
+    #[test]
+    fn test_partial_sort() {
+        let mut seed = 29;
+        let vec: Vec<u64> = (0..2000).map(|_| random(&mut seed) % 300).collect();
+        let mut expected = vec.clone();
+        expected.sort();
+
+        for k in [0, 1, 10, 1999, 2000, 5000] {
+            let mut sorted = vec.clone();
+            partial_sort(&mut sorted, k);
+
+            let k = k.min(vec.len());
+            assert_eq!(expected[..k], sorted[..k]);
+
+            // 其余元素只是换了位置
+            sorted[k..].sort();
+            assert_eq!(expected[k..], sorted[k..]);
+        }
+    }
+
+    #[test]
+    fn test_top_k() {
+        let mut seed = 31;
+        let vec: Vec<String> = (0..3000)
+            .map(|_| (random(&mut seed) % 1000).to_string())
+            .collect();
+        let mut expected = vec.clone();
+        expected.sort_by(|a, b| b.cmp(a));
+
+        for k in [0, 1, 100, 3000, 4000] {
+            let top: Vec<String> = top_k(vec.clone(), k).collect();
+            assert_eq!(expected[..k.min(3000)], top[..]);
+        }
+    }
+
+    #[test]
+    fn test_top_k_huge_k() {
+        // k 远大于元素个数时不按 k 分配空间
+        for k in [1 << 40, usize::MAX] {
+            assert!(top_k(0..10, k).eq((0..10).rev()));
+            assert!(top_k((0..10).filter(|x| x % 2 == 0), k).eq([8, 6, 4, 2, 0]));
+        }
+    }
+}
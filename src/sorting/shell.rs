@@ -0,0 +1,227 @@
+use super::{insertion_sort_impl, less_at, Probe};
+use std::cmp::Ordering;
+
+/// # 希尔排序的间隔序列
+///
+/// [`shell_sort`] 依次用从大到小的间隔 h 做插入排序，最后一个间隔总是 1。
+/// 不同的序列比较次数相差很大，见 [`shell_sort_instrumented`](super::shell_sort_instrumented)。
+///
+/// ```
+/// # use algorithm_study::sorting::GapSequence;
+/// #
+/// let gaps = |seq: GapSequence| seq.gaps(1000).collect::<Vec<_>>();
+///
+/// assert_eq!(gaps(GapSequence::Shell), vec![500, 250, 125, 62, 31, 15, 7, 3, 1]);
+/// assert_eq!(gaps(GapSequence::Knuth), vec![364, 121, 40, 13, 4, 1]);
+/// assert_eq!(gaps(GapSequence::Sedgewick), vec![281, 77, 23, 8, 1]);
+/// assert_eq!(gaps(GapSequence::Ciura), vec![701, 301, 132, 57, 23, 10, 4, 1]);
+/// assert_eq!(gaps(GapSequence::Tokuda), vec![525, 233, 103, 46, 20, 9, 4, 1]);
+/// ```
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GapSequence {
+    /// Shell（1959）：n/2, n/4, ..., 1，最坏 O(n²)
+    Shell,
+    /// Knuth（1973）：(3^k - 1) / 2，即 1, 4, 13, 40, ...，最坏 O(n^1.5)
+    Knuth,
+    /// Sedgewick（1986）：1 以及 4^k + 3·2^(k-1) + 1，即 1, 8, 23, 77, ...，最坏 O(n^(4/3))
+    Sedgewick,
+    /// Ciura（2001）：实验得到的 1, 4, 10, 23, 57, 132, 301, 701, 1750，之后每次乘以 2.25
+    #[default]
+    Ciura,
+    /// Tokuda（1992）：h_k = ⌈h'_k⌉，h'_k = 2.25·h'_(k-1) + 1，即 1, 4, 9, 20, 46, ...
+    Tokuda,
+}
+
+/// Ciura 序列中实验得到的部分
+const CIURA: [usize; 9] = [1, 4, 10, 23, 57, 132, 301, 701, 1750];
+
+impl GapSequence {
+    /// 对长度为 `len` 的数组排序时，从大到小依次使用的间隔，都小于 `len`。
+    pub fn gaps(self, len: usize) -> impl Iterator<Item = usize> {
+        (0..self.count(len)).rev().map(move |k| self.gap(len, k))
+    }
+
+    /// 小于 `len` 的间隔个数
+    fn count(self, len: usize) -> usize {
+        match self {
+            // len 能连续减半的次数
+            GapSequence::Shell => len.checked_ilog2().unwrap_or(0) as usize,
+            _ => (0..).take_while(|&k| self.gap(len, k) < len).count(),
+        }
+    }
+
+    /// 从小到大第 `k` 个间隔，第 0 个是 1；太大时饱和到 `usize::MAX`
+    fn gap(self, len: usize, k: usize) -> usize {
+        match self {
+            GapSequence::Shell => len >> (self.count(len) - k),
+            GapSequence::Knuth => {
+                (0..k).fold(1, |h: usize, _| h.saturating_mul(3).saturating_add(1))
+            }
+            GapSequence::Sedgewick => match k {
+                0 => 1,
+                _ => 1usize
+                    .checked_shl(2 * k as u32)
+                    .and_then(|a| a.checked_add(3 << (k - 1)))
+                    .map_or(usize::MAX, |h| h + 1),
+            },
+            GapSequence::Ciura => match CIURA.get(k) {
+                Some(&h) => h,
+                // 乘以 2.25 并向下取整
+                None => (CIURA.len()..=k).fold(CIURA[CIURA.len() - 1], |h, _| {
+                    h.saturating_mul(2).saturating_add(h / 4)
+                }),
+            },
+            GapSequence::Tokuda => {
+                let h = (0..k).fold(1.0f64, |h, _| 2.25 * h + 1.0);
+                h.ceil() as usize
+            }
+        }
+    }
+}
+
+/// # 希尔排序
+///
+/// 插入排序每次只能把元素移动一位，对离最终位置很远的元素很慢。
+/// 希尔排序先用较大的间隔 h，对每隔 h 个元素组成的子序列做插入排序，让元素大步地靠近最终位置，
+/// 再逐步缩小间隔，最后用间隔 1 做一次普通的插入排序，此时数组已经基本有序，很快就能完成。
+///
+/// 不递归，也不分配辅助空间，适合中等规模的数组。
+/// 复杂度取决于间隔序列 `gaps`，不知道选哪个时用 `GapSequence::default()`。不稳定。
+///
+/// ```
+/// # use algorithm_study::sorting::{shell_sort, GapSequence};
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![1];
+/// let mut vec_2 = vec![3,5,2,4,1];
+/// let mut vec_3: Vec<i32> = (0..10_000).rev().collect();
+///
+/// shell_sort(&mut vec_0, GapSequence::default());
+/// shell_sort(&mut vec_1, GapSequence::default());
+/// shell_sort(&mut vec_2, GapSequence::Knuth);
+/// shell_sort(&mut vec_3, GapSequence::Sedgewick);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1]);
+/// assert_eq!(vec_2, vec![1,2,3,4,5]);
+/// assert!(vec_3.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+///
+pub fn shell_sort<T>(vec: &mut [T], gaps: GapSequence)
+where
+    T: Ord,
+{
+    shell_sort_impl(vec, gaps, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 希尔排序（自定义比较）
+///
+/// 与 [`shell_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::{shell_sort_by, GapSequence};
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// shell_sort_by(&mut vec, GapSequence::Tokuda, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn shell_sort_by<T, F>(vec: &mut [T], gaps: GapSequence, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    shell_sort_impl(
+        vec,
+        gaps,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 希尔排序（按键排序）
+///
+/// 与 [`shell_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::{shell_sort_by_key, GapSequence};
+/// #
+/// let mut vec = vec![-3i32, 1, -2];
+///
+/// shell_sort_by_key(&mut vec, GapSequence::Ciura, |x| x.abs());
+///
+/// assert_eq!(vec, vec![1, -2, -3]);
+/// ```
+///
+pub fn shell_sort_by_key<T, K, F>(vec: &mut [T], gaps: GapSequence, mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    shell_sort_impl(vec, gaps, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+pub(super) fn shell_sort_impl<T, F, P>(vec: &mut [T], gaps: GapSequence, is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    for gap in gaps.gaps(vec.len()) {
+        if gap == 1 {
+            insertion_sort_impl(vec, is_less, probe);
+        } else {
+            gapped_insertion_sort(vec, gap, is_less, probe);
+        }
+    }
+}
+
+/// 对 `vec[i], vec[i + gap], vec[i + 2·gap], ...` 组成的每个子序列做插入排序
+fn gapped_insertion_sort<T, F, P>(vec: &mut [T], gap: usize, is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    for i in gap..vec.len() {
+        // 子序列中的元素不相邻，不能整体右移，只能逐个交换到前面
+        let mut j = i;
+        while j >= gap && less_at(vec, j, j - gap, is_less, probe) {
+            vec.swap(j - gap, j);
+            probe.swap(j - gap, j);
+            j -= gap;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SEQUENCES: [GapSequence; 5] = [
+        GapSequence::Shell,
+        GapSequence::Knuth,
+        GapSequence::Sedgewick,
+        GapSequence::Ciura,
+        GapSequence::Tokuda,
+    ];
+
+    #[test]
+    fn test_gaps() {
+        for seq in SEQUENCES {
+            for len in [0, 1, 2, 3, 10, 1000, 1 << 20, usize::MAX] {
+                let gaps: Vec<usize> = seq.gaps(len).collect();
+
+                // 严格递减，都小于 len，最后一个是 1
+                assert!(gaps.windows(2).all(|w| w[0] > w[1]), "{:?}", seq);
+                assert!(gaps.iter().all(|&h| h < len), "{:?}", seq);
+                assert_eq!(gaps.last().copied(), (len > 1).then_some(1), "{:?}", seq);
+            }
+        }
+
+        let ciura: Vec<usize> = GapSequence::Ciura.gaps(10_000).collect();
+        assert_eq!(ciura[..2], [8858, 3937]);
+        let tokuda: Vec<usize> = GapSequence::Tokuda.gaps(10_000).collect();
+        assert_eq!(tokuda[..3], [5985, 2660, 1182]);
+    }
+}
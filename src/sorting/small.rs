@@ -0,0 +1,403 @@
+use super::{less_at, Probe};
+use std::cmp::Ordering;
+use std::mem::ManuallyDrop;
+use std::ptr;
+
+/// 已知比较器最少的排序网络，`NETWORKS[n]` 对 n 个元素排序，n < 2 时不需要比较。
+///
+/// 每一对 `(i, j)` 都满足 `i < j`，较小的元素换到 `i`；n ≤ 12 时这些网络的大小已被证明是最优的，
+/// 13 ≤ n ≤ 16 时是目前已知最小的，是否最优尚未证明。n = 14、15 的网络由 n = 16 的 Green 网络
+/// 去掉输入得到。
+#[rustfmt::skip]
+const NETWORKS: [&[(usize, usize)]; 17] = [
+    &[],
+    &[],
+    &[(0, 1)],
+    &[(0, 2), (0, 1), (1, 2)],
+    &[(0, 2), (1, 3), (0, 1), (2, 3), (1, 2)],
+    &[
+        (0, 3), (1, 4), (0, 2), (1, 3), (0, 1), (2, 4), (1, 2), (3, 4), (2, 3),
+    ],
+    &[
+        (0, 5), (1, 3), (2, 4), (1, 2), (3, 4), (0, 3), (2, 5), (0, 1), (2, 3), (4, 5), (1, 2),
+        (3, 4),
+    ],
+    &[
+        (0, 6), (2, 3), (4, 5), (0, 2), (1, 4), (3, 6), (0, 1), (2, 5), (3, 4), (1, 2), (4, 6),
+        (2, 3), (4, 5), (1, 2), (3, 4), (5, 6),
+    ],
+    &[
+        (0, 2), (1, 3), (4, 6), (5, 7), (0, 4), (1, 5), (2, 6), (3, 7), (0, 1), (2, 3), (4, 5),
+        (6, 7), (2, 4), (3, 5), (1, 4), (3, 6), (1, 2), (3, 4), (5, 6),
+    ],
+    &[
+        (0, 3), (1, 7), (2, 5), (4, 8), (0, 7), (2, 4), (3, 8), (5, 6), (0, 2), (1, 3), (4, 5),
+        (7, 8), (1, 4), (3, 6), (5, 7), (0, 1), (2, 4), (3, 5), (6, 8), (2, 3), (4, 5), (6, 7),
+        (1, 2), (3, 4), (5, 6),
+    ],
+    &[
+        (0, 8), (1, 9), (2, 7), (3, 5), (4, 6), (0, 2), (1, 4), (5, 8), (7, 9), (0, 3), (2, 4),
+        (5, 7), (6, 9), (0, 1), (3, 6), (8, 9), (1, 5), (2, 3), (4, 8), (6, 7), (1, 2), (3, 5),
+        (4, 6), (7, 8), (2, 3), (4, 5), (6, 7), (3, 4), (5, 6),
+    ],
+    &[
+        (0, 9), (1, 6), (2, 4), (3, 7), (5, 8), (0, 1), (3, 5), (4, 10), (6, 9), (7, 8), (1, 3),
+        (2, 5), (4, 7), (8, 10), (0, 4), (1, 2), (3, 7), (5, 9), (6, 8), (0, 1), (2, 6), (4, 5),
+        (7, 8), (9, 10), (2, 4), (3, 6), (5, 7), (8, 9), (1, 2), (3, 4), (5, 6), (7, 8), (2, 3),
+        (4, 5), (6, 7),
+    ],
+    &[
+        (0, 8), (1, 7), (2, 6), (3, 11), (4, 10), (5, 9), (0, 1), (2, 5), (3, 4), (6, 9), (7, 8),
+        (10, 11), (0, 2), (1, 6), (5, 10), (9, 11), (0, 3), (1, 2), (4, 6), (5, 7), (8, 11),
+        (9, 10), (1, 4), (3, 5), (6, 8), (7, 10), (1, 3), (2, 5), (6, 9), (8, 10), (2, 3), (4, 5),
+        (6, 7), (8, 9), (4, 6), (5, 7), (3, 4), (5, 6), (7, 8),
+    ],
+    &[
+        (0, 12), (1, 10), (2, 9), (3, 7), (5, 11), (6, 8), (1, 6), (2, 3), (4, 11), (7, 9), (8, 10),
+        (0, 4), (1, 2), (3, 6), (7, 8), (9, 10), (11, 12), (4, 6), (5, 9), (8, 11), (10, 12),
+        (0, 5), (3, 8), (4, 7), (6, 11), (9, 10), (0, 1), (2, 5), (6, 9), (7, 8), (10, 11), (1, 3),
+        (2, 4), (5, 6), (9, 10), (1, 2), (3, 4), (5, 7), (6, 8), (2, 3), (4, 5), (6, 7), (8, 9),
+        (3, 4), (5, 6),
+    ],
+    &[
+        (1, 12), (2, 13), (3, 11), (4, 8), (0, 6), (5, 7), (9, 10), (1, 5), (2, 9), (3, 4), (8, 11),
+        (10, 13), (7, 12), (0, 1), (2, 3), (6, 8), (5, 9), (7, 10), (11, 13), (0, 2), (1, 3),
+        (4, 7), (5, 6), (8, 9), (11, 12), (1, 2), (3, 11), (4, 5), (6, 10), (7, 8), (12, 13),
+        (1, 4), (2, 5), (6, 7), (8, 10), (9, 12), (2, 4), (3, 5), (9, 11), (12, 13), (3, 6), (5, 7),
+        (8, 9), (10, 11), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12), (6, 7), (8, 9),
+    ],
+    &[
+        (1, 12), (2, 13), (3, 14), (4, 8), (5, 6), (7, 11), (9, 10), (0, 5), (1, 7), (2, 9), (3, 4),
+        (8, 14), (10, 13), (11, 12), (0, 1), (2, 3), (4, 5), (6, 8), (7, 9), (10, 11), (13, 14),
+        (0, 2), (1, 3), (4, 10), (5, 11), (6, 7), (8, 9), (12, 13), (1, 2), (3, 12), (4, 6), (5, 7),
+        (8, 10), (9, 11), (13, 14), (1, 4), (2, 6), (5, 8), (7, 10), (9, 13), (11, 14), (2, 4),
+        (3, 6), (9, 12), (11, 13), (3, 5), (6, 8), (7, 9), (10, 12), (3, 4), (5, 6), (7, 8),
+        (9, 10), (11, 12), (6, 7), (8, 9),
+    ],
+    &[
+        (0, 13), (1, 12), (2, 15), (3, 14), (4, 8), (5, 6), (7, 11), (9, 10), (0, 5), (1, 7),
+        (2, 9), (3, 4), (6, 13), (8, 14), (10, 15), (11, 12), (0, 1), (2, 3), (4, 5), (6, 8),
+        (7, 9), (10, 11), (12, 13), (14, 15), (0, 2), (1, 3), (4, 10), (5, 11), (6, 7), (8, 9),
+        (12, 14), (13, 15), (1, 2), (3, 12), (4, 6), (5, 7), (8, 10), (9, 11), (13, 14), (1, 4),
+        (2, 6), (5, 8), (7, 10), (9, 13), (11, 14), (2, 4), (3, 6), (9, 12), (11, 13), (3, 5),
+        (6, 8), (7, 9), (10, 12), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12), (6, 7), (8, 9),
+    ],
+];
+
+/// # 排序网络
+///
+/// 排序网络是一串固定的“比较-交换”操作：比较 `arr[i]` 与 `arr[j]`，顺序不对就交换。
+/// 比较哪两个位置只与 `N` 有关，与元素的值无关，没有难以预测的分支，
+/// 适合在热点循环里对很短的定长数组排序，例如中值滤波器的窗口。
+///
+/// `N ≤ 16` 时使用已知比较器最少的网络（例如 `N = 8` 时只需 19 次比较，`N = 16` 时 60 次），
+/// 其中 `N ≤ 12` 的已被证明是最优的。更长的数组使用 Batcher 奇偶归并网络，
+/// 比较次数为 O(N log² N)，不是最优的，适合 32 以内的长度。
+/// 每次比较-交换用指针选择代替分支，编译后是条件传送指令。不稳定。
+///
+/// ```
+/// # use algorithm_study::sorting::sort_network;
+/// #
+/// let mut arr_0: [i32; 0] = [];
+/// let mut arr_1 = [3, 5, 2, 4, 1];
+/// let mut arr_2 = [9, 1, 8, 2, 7, 3, 6, 4, 5, 0, 11, 10, 15, 13, 12, 14];
+///
+/// sort_network(&mut arr_0);
+/// sort_network(&mut arr_1);
+/// sort_network(&mut arr_2);
+///
+/// assert_eq!(arr_1, [1, 2, 3, 4, 5]);
+/// assert!(arr_2.windows(2).all(|w| w[0] < w[1]));
+/// ```
+///
+pub fn sort_network<T, const N: usize>(arr: &mut [T; N])
+where
+    T: Ord,
+{
+    sort_network_impl(arr, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 排序网络（自定义比较）
+///
+/// 与 [`sort_network`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::sort_network_by;
+/// #
+/// let mut arr = [3, 5, 2, 4, 1];
+///
+/// sort_network_by(&mut arr, |a, b| b.cmp(a));
+///
+/// assert_eq!(arr, [5, 4, 3, 2, 1]);
+/// ```
+///
+pub fn sort_network_by<T, F, const N: usize>(arr: &mut [T; N], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort_network_impl(
+        arr,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 排序网络（按键排序）
+///
+/// 与 [`sort_network`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::sort_network_by_key;
+/// #
+/// let mut arr = [-3i32, 1, -2];
+///
+/// sort_network_by_key(&mut arr, |x| x.abs());
+///
+/// assert_eq!(arr, [1, -2, -3]);
+/// ```
+///
+pub fn sort_network_by_key<T, K, F, const N: usize>(arr: &mut [T; N], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    sort_network_impl(arr, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+/// 用排序网络对 `vec` 排序，网络由 `vec.len()` 决定。
+pub(super) fn sort_network_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let len = vec.len();
+
+    if let Some(network) = NETWORKS.get(len) {
+        for &(i, j) in network.iter() {
+            swap_if_less(vec, i, j, is_less, probe);
+        }
+        return;
+    }
+
+    // Batcher 奇偶归并网络：p 是已经有序的块长，每轮把相邻的两块归并成一块，
+    // 归并时比较间隔 k 的元素，只比较同一个 2p 块内的两个位置
+    let mut p = 1;
+    while p < len {
+        let mut k = p;
+        while k > 0 {
+            let mut j = k % p;
+            while j + k < len {
+                for i in j..(j + k).min(len - k) {
+                    if i / (2 * p) == (i + k) / (2 * p) {
+                        swap_if_less(vec, i, i + k, is_less, probe);
+                    }
+                }
+                j += 2 * k;
+            }
+            k /= 2;
+        }
+        p *= 2;
+    }
+}
+
+/// 比较 `vec[i]` 与 `vec[j]`（`i < j`），`vec[j]` 较小时交换两者。
+///
+/// 不用 `if` 决定是否交换，而是根据比较结果选出读写的地址，两种情况下执行的指令相同。
+fn swap_if_less<T, F, P>(vec: &mut [T], i: usize, j: usize, is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let swap = less_at(vec, j, i, is_less, probe);
+
+    // SAFETY: less_at 已经检查过 i、j 都在 vec 范围内；比较函数 panic 时还没有移动任何元素，
+    // 之后的读写不会 panic，tmp 中的元素总会写回 vec。
+    // a、b 由同一个指针得到，读写期间不再借用 vec。
+    unsafe {
+        let base = vec.as_mut_ptr();
+        let a = base.add(i);
+        let b = base.add(j);
+
+        // 交换时 a <- b、b <- a，否则 a <- a、b <- b
+        let tmp = ManuallyDrop::new(ptr::read(if swap { a } else { b }));
+        ptr::copy(if swap { b } else { a }, a, 1);
+        ptr::copy_nonoverlapping(&*tmp, b, 1);
+    }
+
+    probe.swap_if(i, j, swap);
+}
+
+/// # 无分支插入排序
+///
+/// 二分插入排序：用二分查找在已排好的前缀中找到插入位置，再把中间的元素整体右移一位。
+/// 二分查找的循环次数只与前缀的长度有关，每次用条件传送选择左半或右半，
+/// 比较结果不影响控制流，避免了普通插入排序在内层循环出口处的分支预测失败。
+///
+/// 比较次数 O(n log n)，移动次数 O(n²)，适合很短的数组。
+/// 插入位置取在相等元素之后，排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::branchless_insertion_sort;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![1];
+/// let mut vec_2 = vec![3,5,2,4,1];
+/// let mut vec_3 = vec!["b".to_string(), "c".to_string(), "a".to_string()];
+///
+/// branchless_insertion_sort(&mut vec_0);
+/// branchless_insertion_sort(&mut vec_1);
+/// branchless_insertion_sort(&mut vec_2);
+/// branchless_insertion_sort(&mut vec_3);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1]);
+/// assert_eq!(vec_2, vec![1,2,3,4,5]);
+/// assert_eq!(vec_3, vec!["a", "b", "c"]);
+/// ```
+///
+pub fn branchless_insertion_sort<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    branchless_insertion_sort_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 无分支插入排序（自定义比较）
+///
+/// 与 [`branchless_insertion_sort`] 相同，但使用 `compare` 决定元素顺序。排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::branchless_insertion_sort_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// branchless_insertion_sort_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn branchless_insertion_sort_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    branchless_insertion_sort_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 无分支插入排序（按键排序）
+///
+/// 与 [`branchless_insertion_sort`] 相同，但按 `f` 提取的键排序。排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::branchless_insertion_sort_by_key;
+/// #
+/// let mut vec = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+///
+/// branchless_insertion_sort_by_key(&mut vec, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+/// ```
+///
+pub fn branchless_insertion_sort_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    branchless_insertion_sort_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+pub(super) fn branchless_insertion_sort_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    for i in 1..vec.len() {
+        // 插入位置在 [base, base + size] 中，即 vec[..i] 里第一个大于 vec[i] 的位置
+        let mut base = 0;
+        let mut size = i;
+        while size > 1 {
+            let half = size / 2;
+            let mid = base + half;
+            base = if less_at(vec, i, mid, is_less, probe) {
+                base
+            } else {
+                mid
+            };
+            size -= half;
+        }
+        let idx = base + !less_at(vec, i, base, is_less, probe) as usize;
+
+        if idx < i {
+            vec[idx..=i].rotate_right(1);
+            probe.insert(i, idx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::random;
+
+    #[test]
+    fn test_zero_one() {
+        // 0-1 原理：排序网络能排好所有只含 0 和 1 的输入，就能排好任意输入
+        for len in 0..=16 {
+            for bits in 0u32..1 << len {
+                let mut vec: Vec<u32> = (0..len).map(|i| bits >> i & 1).collect();
+                sort_network_impl(&mut vec, &mut |a: &u32, b: &u32| a < b, &());
+                assert!(vec.windows(2).all(|w| w[0] <= w[1]), "{} {:b}", len, bits);
+            }
+        }
+    }
+
+    #[test]
+    fn test_network_size() {
+        let best_known = [0, 0, 1, 3, 5, 9, 12, 16, 19, 25, 29, 35, 39, 45, 51, 56, 60];
+        for (network, size) in NETWORKS.iter().zip(best_known) {
+            assert_eq!(network.len(), size);
+            assert!(network.iter().all(|&(i, j)| i < j));
+        }
+    }
+
+    #[test]
+    fn test_const_len() {
+        let mut seed = 5;
+
+        fn check<const N: usize>(seed: &mut u64) {
+            let mut arr = [0u64; N];
+            arr.iter_mut().for_each(|x| *x = random(seed) % 10);
+            let mut expected = arr;
+            expected.sort();
+
+            sort_network(&mut arr);
+            assert_eq!(arr, expected);
+        }
+
+        for _ in 0..100 {
+            check::<2>(&mut seed);
+            check::<7>(&mut seed);
+            check::<12>(&mut seed);
+            check::<17>(&mut seed);
+            check::<32>(&mut seed);
+            check::<33>(&mut seed);
+        }
+    }
+
+    #[test]
+    fn test_drop() {
+        // 比较-交换只是搬动元素，不会复制或丢失需要 drop 的元素
+        let mut arr = ["d", "b", "a", "c", "e"].map(String::from);
+        sort_network(&mut arr);
+        assert_eq!(arr, ["a", "b", "c", "d", "e"]);
+
+        let mut vec: Vec<String> = (0..40).rev().map(|i| i.to_string()).collect();
+        let mut expected = vec.clone();
+        expected.sort();
+        sort_network_impl(&mut vec, &mut |a: &String, b: &String| a < b, &());
+        assert_eq!(vec, expected);
+    }
+}
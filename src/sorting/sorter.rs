@@ -0,0 +1,417 @@
+use super::{
+    branchless_insertion_sort, bubble_sort, counting_sort, heap_sort, insertion_sort, intro_sort,
+    merge_sort_bottom_up, merge_sort_in_place, merge_sort_slice, par_merge_sort, par_quick_sort,
+    quick_sort, quick_sort_3way, quick_sort_iterative, radix_sort_lsd, radix_sort_msd,
+    selection_sort, shell_sort, tim_sort, GapSequence, RadixKey,
+};
+use std::fmt;
+
+/// # 排序算法
+///
+/// 统一各个排序函数的调用方式，并给出算法的性质，
+/// 便于测试、基准测试和命令行工具遍历所有算法，见 [`sorters`]。
+///
+/// 每个算法对应一个没有字段的类型，对元素类型的要求与对应的排序函数相同。
+///
+/// ```
+/// # use algorithm_study::sorting::{Complexity, HeapSort, Sorter};
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// HeapSort.sort(&mut vec);
+///
+/// assert_eq!(vec, vec![1,2,3,4,5]);
+/// assert_eq!(Sorter::<i32>::name(&HeapSort), "heap_sort");
+/// assert_eq!(Sorter::<i32>::worst_case(&HeapSort), Complexity::Linearithmic);
+/// ```
+///
+pub trait Sorter<T> {
+    /// 算法的名称，与对应的排序函数同名。
+    fn name(&self) -> &'static str;
+
+    /// 排序是否稳定，即相等的元素是否保持原来的先后顺序。
+    fn is_stable(&self) -> bool;
+
+    /// 是否原地排序：除了递归所用的栈以外，只占用 O(1) 的辅助空间。
+    fn is_in_place(&self) -> bool;
+
+    /// 最坏情况下的时间复杂度。
+    fn worst_case(&self) -> Complexity;
+
+    /// 对 `vec` 升序排序。
+    fn sort(&self, vec: &mut [T]);
+}
+
+/// # 时间复杂度
+///
+/// 按增长的快慢排列，可以直接比较大小。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Complexity {
+    /// 与元素个数成线性关系，系数取决于键的长度或取值范围，如计数排序的 O(n + k)
+    Linear,
+    /// O(n log n)
+    Linearithmic,
+    /// O(n log² n)
+    LogSquared,
+    /// O(n²)
+    Quadratic,
+}
+
+impl fmt::Display for Complexity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Complexity::Linear => "O(n)",
+            Complexity::Linearithmic => "O(n log n)",
+            Complexity::LogSquared => "O(n log² n)",
+            Complexity::Quadratic => "O(n²)",
+        };
+        f.write_str(s)
+    }
+}
+
+macro_rules! sorter {
+    ($(
+        $(#[$attr:meta])*
+        $ty:ident: $name:ident<$($bound:path),+> {
+            stable: $stable:expr,
+            in_place: $in_place:expr,
+            worst_case: $worst_case:ident,
+            sort: |$vec:ident| $sort:expr $(,)?
+        }
+    )*) => {$(
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, Default)]
+        pub struct $ty;
+
+        impl<T: $($bound +)+> Sorter<T> for $ty {
+            fn name(&self) -> &'static str {
+                stringify!($name)
+            }
+
+            fn is_stable(&self) -> bool {
+                $stable
+            }
+
+            fn is_in_place(&self) -> bool {
+                $in_place
+            }
+
+            fn worst_case(&self) -> Complexity {
+                Complexity::$worst_case
+            }
+
+            fn sort(&self, $vec: &mut [T]) {
+                $sort
+            }
+        }
+    )*};
+}
+
+sorter! {
+    /// [`bubble_sort`](super::bubble_sort)
+    BubbleSort: bubble_sort<Ord> {
+        stable: true,
+        in_place: true,
+        worst_case: Quadratic,
+        sort: |vec| bubble_sort(vec),
+    }
+
+    /// [`insertion_sort`](super::insertion_sort)
+    InsertionSort: insertion_sort<Ord> {
+        stable: true,
+        in_place: true,
+        worst_case: Quadratic,
+        sort: |vec| insertion_sort(vec),
+    }
+
+    /// [`branchless_insertion_sort`](super::branchless_insertion_sort)
+    BranchlessInsertionSort: branchless_insertion_sort<Ord> {
+        stable: true,
+        in_place: true,
+        worst_case: Quadratic,
+        sort: |vec| branchless_insertion_sort(vec),
+    }
+
+    /// [`selection_sort`](super::selection_sort)
+    SelectionSort: selection_sort<Ord> {
+        stable: false,
+        in_place: true,
+        worst_case: Quadratic,
+        sort: |vec| selection_sort(vec),
+    }
+
+    /// [`shell_sort`](super::shell_sort)，使用默认的间隔序列，最坏情况取已证明的上界
+    ShellSort: shell_sort<Ord> {
+        stable: false,
+        in_place: true,
+        worst_case: Quadratic,
+        sort: |vec| shell_sort(vec, GapSequence::default()),
+    }
+
+    /// [`merge_sort_slice`](super::merge_sort_slice)
+    MergeSort: merge_sort<Ord> {
+        stable: true,
+        in_place: false,
+        worst_case: Linearithmic,
+        sort: |vec| merge_sort_slice(vec),
+    }
+
+    /// [`merge_sort_bottom_up`](super::merge_sort_bottom_up)
+    MergeSortBottomUp: merge_sort_bottom_up<Ord> {
+        stable: true,
+        in_place: false,
+        worst_case: Linearithmic,
+        sort: |vec| merge_sort_bottom_up(vec),
+    }
+
+    /// [`merge_sort_in_place`](super::merge_sort_in_place)
+    MergeSortInPlace: merge_sort_in_place<Ord> {
+        stable: true,
+        in_place: true,
+        worst_case: LogSquared,
+        sort: |vec| merge_sort_in_place(vec),
+    }
+
+    /// [`quick_sort`](super::quick_sort)
+    QuickSort: quick_sort<Ord> {
+        stable: false,
+        in_place: true,
+        worst_case: Linearithmic,
+        sort: |vec| quick_sort(vec),
+    }
+
+    /// [`quick_sort_iterative`](super::quick_sort_iterative)
+    QuickSortIterative: quick_sort_iterative<Ord> {
+        stable: false,
+        in_place: true,
+        worst_case: Linearithmic,
+        sort: |vec| quick_sort_iterative(vec),
+    }
+
+    /// [`quick_sort_3way`](super::quick_sort_3way)
+    QuickSort3Way: quick_sort_3way<Ord> {
+        stable: false,
+        in_place: true,
+        worst_case: Linearithmic,
+        sort: |vec| quick_sort_3way(vec),
+    }
+
+    /// [`heap_sort`](super::heap_sort)
+    HeapSort: heap_sort<Ord> {
+        stable: false,
+        in_place: true,
+        worst_case: Linearithmic,
+        sort: |vec| heap_sort(vec),
+    }
+
+    /// [`intro_sort`](super::intro_sort)
+    IntroSort: intro_sort<Ord> {
+        stable: false,
+        in_place: true,
+        worst_case: Linearithmic,
+        sort: |vec| intro_sort(vec),
+    }
+
+    /// [`tim_sort`](super::tim_sort)
+    TimSort: tim_sort<Ord> {
+        stable: true,
+        in_place: false,
+        worst_case: Linearithmic,
+        sort: |vec| tim_sort(vec),
+    }
+
+    /// [`par_merge_sort`](super::par_merge_sort)，线程数取默认值
+    ParMergeSort: par_merge_sort<Ord, Send> {
+        stable: true,
+        in_place: false,
+        worst_case: Linearithmic,
+        sort: |vec| par_merge_sort(vec, 0),
+    }
+
+    /// [`par_quick_sort`](super::par_quick_sort)，线程数取默认值
+    ParQuickSort: par_quick_sort<Ord, Send> {
+        stable: false,
+        in_place: true,
+        worst_case: Linearithmic,
+        sort: |vec| par_quick_sort(vec, 0),
+    }
+
+    /// [`counting_sort`](super::counting_sort)，取值范围很大时改用 LSD 基数排序
+    CountingSort: counting_sort<Copy, Into<i128>> {
+        stable: true,
+        in_place: false,
+        worst_case: Linear,
+        sort: |vec| counting_sort(vec),
+    }
+
+    /// [`radix_sort_lsd`](super::radix_sort_lsd)
+    RadixSortLsd: radix_sort_lsd<RadixKey> {
+        stable: true,
+        in_place: false,
+        worst_case: Linear,
+        sort: |vec| radix_sort_lsd(vec),
+    }
+
+    /// [`radix_sort_msd`](super::radix_sort_msd)
+    RadixSortMsd: radix_sort_msd<RadixKey> {
+        stable: false,
+        in_place: true,
+        worst_case: Linear,
+        sort: |vec| radix_sort_msd(vec),
+    }
+}
+
+/// # 所有基于比较的排序算法
+///
+/// 适用于任何实现了 `Ord` 的元素，并行排序还要求元素可以在线程间传递。
+///
+/// ```
+/// # use algorithm_study::sorting::comparison_sorters;
+/// #
+/// for sorter in comparison_sorters() {
+///     let mut vec = vec!["pear", "apple", "fig"];
+///     sorter.sort(&mut vec);
+///
+///     assert_eq!(vec, vec!["apple", "fig", "pear"], "{}", sorter.name());
+/// }
+/// ```
+///
+pub fn comparison_sorters<T>() -> Vec<Box<dyn Sorter<T>>>
+where
+    T: Ord + Send,
+{
+    vec![
+        Box::new(BubbleSort),
+        Box::new(InsertionSort),
+        Box::new(BranchlessInsertionSort),
+        Box::new(SelectionSort),
+        Box::new(ShellSort),
+        Box::new(MergeSort),
+        Box::new(MergeSortBottomUp),
+        Box::new(MergeSortInPlace),
+        Box::new(QuickSort),
+        Box::new(QuickSortIterative),
+        Box::new(QuickSort3Way),
+        Box::new(HeapSort),
+        Box::new(IntroSort),
+        Box::new(TimSort),
+        Box::new(ParMergeSort),
+        Box::new(ParQuickSort),
+    ]
+}
+
+/// # 所有排序算法
+///
+/// 在 [`comparison_sorters`] 之后加上计数排序和基数排序，适用于整数。
+///
+/// ```
+/// # use algorithm_study::sorting::{sorters, Complexity};
+/// #
+/// for sorter in sorters() {
+///     if sorter.worst_case() == Complexity::Quadratic {
+///         continue;
+///     }
+///
+///     let mut vec: Vec<i32> = (0..1000).rev().collect();
+///     sorter.sort(&mut vec);
+///
+///     assert!(vec.windows(2).all(|w| w[0] <= w[1]), "{}", sorter.name());
+/// }
+/// ```
+///
+pub fn sorters<T>() -> Vec<Box<dyn Sorter<T>>>
+where
+    T: Ord + Send + Copy + Into<i128> + RadixKey,
+{
+    let mut sorters = comparison_sorters();
+    sorters.push(Box::new(CountingSort));
+    sorters.push(Box::new(RadixSortLsd));
+    sorters.push(Box::new(RadixSortMsd));
+    sorters
+}
+
+/// # 按名称查找排序算法
+///
+/// 名称与排序函数同名，在 [`sorters`] 中查找，找不到时返回 `None`。
+/// 元素不是整数时用 [`find_comparison_sorter`]。
+///
+/// ```
+/// # use algorithm_study::sorting::find_sorter;
+/// #
+/// let sorter = find_sorter::<u8>("tim_sort").unwrap();
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// sorter.sort(&mut vec);
+///
+/// assert!(sorter.is_stable());
+/// assert_eq!(vec, vec![1,2,3,4,5]);
+/// assert!(find_sorter::<u8>("sleep_sort").is_none());
+/// ```
+///
+pub fn find_sorter<T>(name: &str) -> Option<Box<dyn Sorter<T>>>
+where
+    T: Ord + Send + Copy + Into<i128> + RadixKey,
+{
+    sorters().into_iter().find(|sorter| sorter.name() == name)
+}
+
+/// # 按名称查找基于比较的排序算法
+///
+/// 与 [`find_sorter`] 相同，但只在 [`comparison_sorters`] 中查找，
+/// 对元素的要求也与它相同，可以用于字符串等任何实现了 `Ord` 的类型。
+///
+/// ```
+/// # use algorithm_study::sorting::find_comparison_sorter;
+/// #
+/// let sorter = find_comparison_sorter::<String>("merge_sort").unwrap();
+/// let mut vec = vec!["pear".to_string(), "apple".to_string(), "fig".to_string()];
+///
+/// sorter.sort(&mut vec);
+///
+/// assert_eq!(vec, vec!["apple", "fig", "pear"]);
+/// assert!(find_comparison_sorter::<String>("radix_sort_lsd").is_none());
+/// ```
+///
+pub fn find_comparison_sorter<T>(name: &str) -> Option<Box<dyn Sorter<T>>>
+where
+    T: Ord + Send,
+{
+    comparison_sorters()
+        .into_iter()
+        .find(|sorter| sorter.name() == name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let sorters = sorters::<u32>();
+        let mut names: Vec<&str> = sorters.iter().map(|sorter| sorter.name()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), sorters.len());
+
+        for name in names {
+            assert_eq!(find_sorter::<u32>(name).unwrap().name(), name);
+        }
+
+        for sorter in comparison_sorters::<String>() {
+            let found = find_comparison_sorter::<String>(sorter.name()).unwrap();
+            assert_eq!(found.name(), sorter.name());
+        }
+    }
+
+    #[test]
+    fn test_sort() {
+        for sorter in sorters::<i64>() {
+            let mut vec: Vec<i64> = (0..300).map(|i| (i * 7919) % 301 - 150).collect();
+            let mut expected = vec.clone();
+            expected.sort();
+
+            sorter.sort(&mut vec);
+            assert_eq!(vec, expected, "{}", sorter.name());
+        }
+    }
+}
@@ -0,0 +1,755 @@
+use super::probe::{Fork, Probe};
+use super::{
+    bubble_sort_impl, heap, in_place, insertion_sort_impl, intro, iterative, merge_sort_impl,
+    parallel, quick_sort_impl, radix, selection_sort_impl, shell, small, three_way, tim,
+    GapSequence, RadixKey,
+};
+use std::cell::Cell;
+
+/// # 排序统计
+///
+/// `*_instrumented` 系列函数的返回值，记录一次排序的工作量：
+///
+/// - `comparisons`：元素（或键）的比较次数；
+/// - `swaps`：两个元素的交换次数；
+/// - `moves`：交换之外的元素移动次数，例如插入排序的整体右移、归并时进出辅助空间；
+/// - `max_depth`：最大递归深度，最外层调用为 0；
+/// - `allocations`、`allocated`：辅助空间的分配次数，以及总共能放下多少个元素、下标或计数。
+///
+/// ```
+/// # use algorithm_study::sorting::{insertion_sort_instrumented, SortStats};
+/// #
+/// let mut vec = vec![2,1,3];
+///
+/// let stats = insertion_sort_instrumented(&mut vec);
+///
+/// assert_eq!(vec, vec![1,2,3]);
+/// assert_eq!(
+///     stats,
+///     SortStats { comparisons: 2, moves: 2, ..SortStats::default() }
+/// );
+/// ```
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortStats {
+    pub comparisons: usize,
+    pub swaps: usize,
+    pub moves: usize,
+    pub max_depth: usize,
+    pub allocations: usize,
+    pub allocated: usize,
+}
+
+/// 统计用的探针
+#[derive(Default)]
+struct Counter {
+    stats: Cell<SortStats>,
+    depth: Cell<usize>,
+}
+
+impl Counter {
+    fn update(&self, f: impl FnOnce(&mut SortStats)) {
+        let mut stats = self.stats.get();
+        f(&mut stats);
+        self.stats.set(stats);
+    }
+}
+
+impl Probe for Counter {
+    fn compare(&self) {
+        self.update(|s| s.comparisons += 1);
+    }
+
+    fn swap(&self, _i: usize, _j: usize) {
+        self.update(|s| s.swaps += 1);
+    }
+
+    fn swap_if(&self, _i: usize, _j: usize, swap: bool) {
+        self.update(|s| s.swaps += swap as usize);
+    }
+
+    fn moves(&self, n: usize) {
+        self.update(|s| s.moves += n);
+    }
+
+    fn enter(&self) {
+        let depth = self.depth.get() + 1;
+        self.depth.set(depth);
+        self.update(|s| s.max_depth = s.max_depth.max(depth));
+    }
+
+    fn leave(&self) {
+        self.depth.set(self.depth.get() - 1);
+    }
+
+    fn alloc(&self, n: usize) {
+        // 容量为 0 的 Vec 不会真正分配
+        if n > 0 {
+            self.update(|s| {
+                s.allocations += 1;
+                s.allocated += n;
+            });
+        }
+    }
+}
+
+impl Fork for Counter {
+    fn fork(&self) -> Self {
+        let child = Counter::default();
+        child.depth.set(self.depth.get());
+        child
+    }
+
+    fn join(&self, child: Self) {
+        let c = child.stats.get();
+        self.update(|s| {
+            s.comparisons += c.comparisons;
+            s.swaps += c.swaps;
+            s.moves += c.moves;
+            s.max_depth = s.max_depth.max(c.max_depth);
+            s.allocations += c.allocations;
+            s.allocated += c.allocated;
+        });
+    }
+}
+
+/// 用 `<` 比较，并计入比较次数
+fn counted<'a, T: Ord>(counter: &'a Counter) -> impl FnMut(&T, &T) -> bool + 'a {
+    move |a, b| {
+        counter.compare();
+        a < b
+    }
+}
+
+/// # 冒泡排序（统计）
+///
+/// 与 [`bubble_sort`](super::bubble_sort) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::bubble_sort_instrumented;
+/// #
+/// let mut vec = vec![1,2,3,4,5];
+///
+/// let stats = bubble_sort_instrumented(&mut vec);
+///
+/// // 已有序时只需一轮
+/// assert_eq!(stats.comparisons, 4);
+/// assert_eq!(stats.swaps, 0);
+/// ```
+///
+pub fn bubble_sort_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    bubble_sort_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 插入排序（统计）
+///
+/// 与 [`insertion_sort`](super::insertion_sort) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::insertion_sort_instrumented;
+/// #
+/// let mut vec = vec![5,4,3,2,1];
+///
+/// let stats = insertion_sort_instrumented(&mut vec);
+///
+/// // 逆序时每个元素都移到最前面
+/// assert_eq!(vec, vec![1,2,3,4,5]);
+/// assert_eq!(stats.comparisons, 10);
+/// assert_eq!(stats.moves, 2 + 3 + 4 + 5);
+/// ```
+///
+pub fn insertion_sort_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    insertion_sort_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 无分支插入排序（统计）
+///
+/// 与 [`branchless_insertion_sort`](super::branchless_insertion_sort) 相同，
+/// 同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::branchless_insertion_sort_instrumented;
+/// #
+/// let mut sorted = vec![1,2,3,4,5];
+/// let mut reversed = vec![5,4,3,2,1];
+///
+/// let stats_0 = branchless_insertion_sort_instrumented(&mut sorted);
+/// let stats_1 = branchless_insertion_sort_instrumented(&mut reversed);
+///
+/// // 二分查找的比较次数只与长度有关
+/// assert_eq!(stats_0.comparisons, 9);
+/// assert_eq!(stats_1.comparisons, 9);
+/// // 已有序时不移动元素，逆序时每个元素都移到最前面
+/// assert_eq!(stats_0.moves, 0);
+/// assert_eq!(stats_1.moves, 2 + 3 + 4 + 5);
+/// ```
+///
+pub fn branchless_insertion_sort_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    small::branchless_insertion_sort_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 排序网络（统计）
+///
+/// 与 [`sort_network`](super::sort_network) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::sort_network_instrumented;
+/// #
+/// let mut sorted = [1, 2, 3, 4, 5];
+/// let mut reversed = [5, 4, 3, 2, 1];
+///
+/// let stats_0 = sort_network_instrumented(&mut sorted);
+/// let stats_1 = sort_network_instrumented(&mut reversed);
+///
+/// // 比较次数等于网络中比较器的个数，与输入无关
+/// assert_eq!(stats_0.comparisons, 9);
+/// assert_eq!(stats_1.comparisons, 9);
+/// assert_eq!(stats_0.swaps, 0);
+/// assert_eq!(reversed, [1, 2, 3, 4, 5]);
+/// ```
+///
+pub fn sort_network_instrumented<T: Ord, const N: usize>(arr: &mut [T; N]) -> SortStats {
+    let counter = Counter::default();
+    small::sort_network_impl(arr, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 选择排序（统计）
+///
+/// 与 [`selection_sort`](super::selection_sort) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::selection_sort_instrumented;
+/// #
+/// let mut vec = vec![5,4,3,2,1];
+///
+/// let stats = selection_sort_instrumented(&mut vec);
+///
+/// // 比较次数与输入无关，总是 n(n-1)/2
+/// assert_eq!(stats.comparisons, 10);
+/// assert_eq!(stats.swaps, 2);
+/// ```
+///
+pub fn selection_sort_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    selection_sort_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 归并排序（统计）
+///
+/// 与 [`merge_sort_slice`](super::merge_sort_slice) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_instrumented;
+/// #
+/// let mut vec: Vec<i32> = (0..1024).rev().collect();
+///
+/// let stats = merge_sort_instrumented(&mut vec);
+///
+/// assert_eq!(vec, (0..1024).collect::<Vec<_>>());
+/// // 拆分到 16 个元素为止
+/// assert_eq!(stats.max_depth, 6);
+/// // 辅助空间只分配一次
+/// assert_eq!(stats.allocations, 1);
+/// assert_eq!(stats.allocated, 512);
+/// ```
+///
+pub fn merge_sort_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    merge_sort_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 自底向上归并排序（统计）
+///
+/// 与 [`merge_sort_bottom_up`](super::merge_sort_bottom_up) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::merge_sort_bottom_up_instrumented;
+/// #
+/// let mut vec: Vec<i32> = (0..1024).rev().collect();
+///
+/// let stats = merge_sort_bottom_up_instrumented(&mut vec);
+///
+/// assert_eq!(vec, (0..1024).collect::<Vec<_>>());
+/// // 不递归，辅助空间只分配一次
+/// assert_eq!(stats.max_depth, 0);
+/// assert_eq!(stats.allocations, 1);
+/// assert_eq!(stats.allocated, 512);
+/// ```
+///
+pub fn merge_sort_bottom_up_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    iterative::merge_sort_bottom_up_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 原地归并排序（统计）
+///
+/// 与 [`merge_sort_in_place`](super::merge_sort_in_place) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::{merge_sort_in_place_instrumented, merge_sort_instrumented};
+/// #
+/// let vec: Vec<i32> = (0..1024).rev().collect();
+///
+/// let stats = merge_sort_in_place_instrumented(&mut vec.clone());
+///
+/// // 不分配辅助空间，代价是更多的移动
+/// assert_eq!(stats.allocations, 0);
+/// assert!(stats.moves > merge_sort_instrumented(&mut vec.clone()).moves);
+/// ```
+///
+pub fn merge_sort_in_place_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    in_place::merge_sort_in_place_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 快速排序（统计）
+///
+/// 与 [`quick_sort`](super::quick_sort) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::quick_sort_instrumented;
+/// #
+/// let mut vec: Vec<i32> = (0..1024).collect();
+///
+/// let stats = quick_sort_instrumented(&mut vec);
+///
+/// // 只对较短的一侧递归，深度不超过 log2(n)
+/// assert!(stats.max_depth <= 10);
+/// assert_eq!(stats.allocations, 0);
+/// ```
+///
+pub fn quick_sort_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    quick_sort_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 非递归快速排序（统计）
+///
+/// 与 [`quick_sort_iterative`](super::quick_sort_iterative) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::quick_sort_iterative_instrumented;
+/// #
+/// let mut vec: Vec<i32> = (0..1024).collect();
+///
+/// let stats = quick_sort_iterative_instrumented(&mut vec);
+///
+/// // 待排序的区间保存在显式的栈中，不递归
+/// assert_eq!(stats.max_depth, 0);
+/// assert!(stats.comparisons > 0);
+/// ```
+///
+pub fn quick_sort_iterative_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    iterative::quick_sort_iterative_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 堆排序（统计）
+///
+/// 与 [`heap_sort`](super::heap_sort) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::heap_sort_instrumented;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// let stats = heap_sort_instrumented(&mut vec);
+///
+/// assert_eq!(vec, vec![1,2,3,4,5]);
+/// assert_eq!(stats.max_depth, 0);
+/// assert_eq!(stats.moves, 0);
+/// ```
+///
+pub fn heap_sort_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    heap::heap_sort_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 内省排序（统计）
+///
+/// 与 [`intro_sort`](super::intro_sort) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::{heap_sort_instrumented, intro_sort_instrumented};
+/// #
+/// let vec: Vec<i32> = (0..1000).map(|i| (i * 7919) % 1000).collect();
+///
+/// let intro = intro_sort_instrumented(&mut vec.clone());
+/// let heap = heap_sort_instrumented(&mut vec.clone());
+///
+/// // 以快速排序为主体，比较次数比堆排序少
+/// assert!(intro.comparisons < heap.comparisons);
+/// ```
+///
+pub fn intro_sort_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    intro::intro_sort_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 三路快速排序（统计）
+///
+/// 与 [`quick_sort_3way`](super::quick_sort_3way) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::quick_sort_3way_instrumented;
+/// #
+/// let mut vec = vec![7; 100];
+///
+/// let stats = quick_sort_3way_instrumented(&mut vec);
+///
+/// // 全部相等时只需一轮分区：选 pivot 比较 2 次，其余每个元素比较 2 次
+/// assert_eq!(stats.comparisons, 2 + 2 * 99);
+/// ```
+///
+pub fn quick_sort_3way_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    three_way::quick_sort_3way_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # Timsort（统计）
+///
+/// 与 [`tim_sort`](super::tim_sort) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::tim_sort_instrumented;
+/// #
+/// let mut vec: Vec<i32> = (0..1000).rev().collect();
+///
+/// let stats = tim_sort_instrumented(&mut vec);
+///
+/// // 严格下降的数组是一个 run，翻转即可
+/// assert_eq!(stats.comparisons, 999);
+/// assert_eq!(stats.swaps, 500);
+/// assert_eq!(stats.moves, 0);
+/// ```
+///
+pub fn tim_sort_instrumented<T: Ord>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    tim::tim_sort_impl(vec, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 希尔排序（统计）
+///
+/// 与 [`shell_sort`](super::shell_sort) 相同，同时返回排序过程的 [`SortStats`]，
+/// 可以用来比较不同间隔序列的工作量。
+///
+/// ```
+/// # use algorithm_study::sorting::{shell_sort_instrumented, GapSequence};
+/// #
+/// let vec: Vec<u32> = (0..1000).rev().collect();
+///
+/// let shell = shell_sort_instrumented(&mut vec.clone(), GapSequence::Shell);
+/// let ciura = shell_sort_instrumented(&mut vec.clone(), GapSequence::Ciura);
+///
+/// assert!(ciura.comparisons < shell.comparisons);
+/// assert_eq!(ciura.allocations, 0);
+/// assert_eq!(ciura.max_depth, 0);
+/// ```
+///
+pub fn shell_sort_instrumented<T: Ord>(vec: &mut [T], gaps: GapSequence) -> SortStats {
+    let counter = Counter::default();
+    shell::shell_sort_impl(vec, gaps, &mut counted(&counter), &counter);
+    counter.stats.get()
+}
+
+/// # 计数排序（统计）
+///
+/// 与 [`counting_sort`](super::counting_sort) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::counting_sort_instrumented;
+/// #
+/// let mut vec = vec![3u8, 1, 2, 1];
+///
+/// let stats = counting_sort_instrumented(&mut vec);
+///
+/// // 不比较元素；键、计数和目标位置各分配一次
+/// assert_eq!(vec, vec![1, 1, 2, 3]);
+/// assert_eq!(stats.comparisons, 0);
+/// assert_eq!(stats.allocations, 3);
+/// assert_eq!(stats.allocated, 4 + 3 + 4);
+/// ```
+///
+pub fn counting_sort_instrumented<T>(vec: &mut [T]) -> SortStats
+where
+    T: Copy + Into<i128>,
+{
+    let counter = Counter::default();
+    radix::counting_sort_impl(vec, &counter);
+    counter.stats.get()
+}
+
+/// # LSD 基数排序（统计）
+///
+/// 与 [`radix_sort_lsd`](super::radix_sort_lsd) 相同，同时返回排序过程的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::radix_sort_lsd_instrumented;
+/// #
+/// let mut vec = vec![300u32, 7, 65_536, 0];
+///
+/// let stats = radix_sort_lsd_instrumented(&mut vec);
+///
+/// assert_eq!(vec, vec![0, 7, 300, 65_536]);
+/// assert_eq!(stats.comparisons, 0);
+/// ```
+///
+pub fn radix_sort_lsd_instrumented<T: RadixKey>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    radix::radix_sort_lsd_impl(vec, &counter);
+    counter.stats.get()
+}
+
+/// # MSD 基数排序（统计）
+///
+/// 与 [`radix_sort_msd`](super::radix_sort_msd) 相同，同时返回排序过程的 [`SortStats`]。
+/// 比较次数来自短区间上的插入排序。
+///
+/// ```
+/// # use algorithm_study::sorting::radix_sort_msd_instrumented;
+/// #
+/// let mut vec: Vec<u16> = (0..1000).rev().collect();
+///
+/// let stats = radix_sort_msd_instrumented(&mut vec);
+///
+/// assert_eq!(vec, (0..1000).collect::<Vec<_>>());
+/// assert_eq!(stats.max_depth, 1);
+/// ```
+///
+pub fn radix_sort_msd_instrumented<T: RadixKey>(vec: &mut [T]) -> SortStats {
+    let counter = Counter::default();
+    radix::radix_sort_msd_impl(vec, &counter);
+    counter.stats.get()
+}
+
+/// # 并行归并排序（统计）
+///
+/// 与 [`par_merge_sort`](super::par_merge_sort) 相同，同时返回所有线程汇总的 [`SortStats`]。
+/// 每次并行合并都单独分配辅助空间。
+///
+/// ```
+/// # use algorithm_study::sorting::{merge_sort_instrumented, par_merge_sort_instrumented};
+/// #
+/// let vec: Vec<i32> = (0..100_000).map(|i| (i * 7919) % 100_000).collect();
+///
+/// let seq = merge_sort_instrumented(&mut vec.clone());
+/// let par = par_merge_sort_instrumented(&mut vec.clone(), 4);
+///
+/// // 拆分方式相同，比较次数也相同
+/// assert_eq!(seq.comparisons, par.comparisons);
+/// assert_eq!(seq.max_depth, par.max_depth);
+/// ```
+///
+pub fn par_merge_sort_instrumented<T>(vec: &mut [T], threads: usize) -> SortStats
+where
+    T: Ord + Send,
+{
+    let counter = Counter::default();
+    let threads = parallel::threads_or_default(threads);
+    parallel::par_merge_sort_impl(vec, threads, &|a: &T, b: &T| a < b, &counter);
+    counter.stats.get()
+}
+
+/// # 并行快速排序（统计）
+///
+/// 与 [`par_quick_sort`](super::par_quick_sort) 相同，同时返回所有线程汇总的 [`SortStats`]。
+///
+/// ```
+/// # use algorithm_study::sorting::{par_quick_sort_instrumented, quick_sort_instrumented};
+/// #
+/// let vec: Vec<i32> = (0..100_000).map(|i| (i * 7919) % 100_000).collect();
+///
+/// let seq = quick_sort_instrumented(&mut vec.clone());
+/// let par = par_quick_sort_instrumented(&mut vec.clone(), 4);
+///
+/// // 分区过程相同，比较和交换次数也完全相同
+/// assert_eq!(seq.comparisons, par.comparisons);
+/// assert_eq!(seq.swaps, par.swaps);
+/// ```
+///
+pub fn par_quick_sort_instrumented<T>(vec: &mut [T], threads: usize) -> SortStats
+where
+    T: Ord + Send,
+{
+    let counter = Counter::default();
+    let threads = parallel::threads_or_default(threads);
+    parallel::par_quick_sort_impl(vec, threads, &|a: &T, b: &T| a < b, &counter);
+    counter.stats.get()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::random;
+
+    #[test]
+    fn test_sorted_result() {
+        let mut seed = 3;
+        let vec: Vec<u32> = (0..3000).map(|_| random(&mut seed) as u32 % 500).collect();
+        let mut expected = vec.clone();
+        expected.sort();
+
+        let sorts: [fn(&mut [u32]) -> SortStats; 19] = [
+            bubble_sort_instrumented,
+            insertion_sort_instrumented,
+            branchless_insertion_sort_instrumented,
+            selection_sort_instrumented,
+            merge_sort_instrumented,
+            merge_sort_in_place_instrumented,
+            merge_sort_bottom_up_instrumented,
+            quick_sort_instrumented,
+            quick_sort_iterative_instrumented,
+            heap_sort_instrumented,
+            intro_sort_instrumented,
+            quick_sort_3way_instrumented,
+            tim_sort_instrumented,
+            |v| shell_sort_instrumented(v, GapSequence::Tokuda),
+            counting_sort_instrumented,
+            radix_sort_lsd_instrumented,
+            radix_sort_msd_instrumented,
+            |v| par_merge_sort_instrumented(v, 4),
+            |v| par_quick_sort_instrumented(v, 4),
+        ];
+
+        for sort in sorts {
+            let mut sorted = vec.clone();
+            sort(&mut sorted);
+            assert_eq!(expected, sorted);
+        }
+    }
+
+    #[test]
+    fn test_comparisons_match_callback() {
+        // 统计的比较次数与比较函数被调用的次数一致
+        let mut seed = 11;
+        let vec: Vec<u64> = (0..2000).map(|_| random(&mut seed) % 100).collect();
+
+        let mut count = 0;
+        crate::sorting::quick_sort_by(&mut vec.clone(), |a, b| {
+            count += 1;
+            a.cmp(b)
+        });
+        assert_eq!(count, quick_sort_instrumented(&mut vec.clone()).comparisons);
+
+        let mut count = 0;
+        crate::sorting::tim_sort_by(&mut vec.clone(), |a, b| {
+            count += 1;
+            a.cmp(b)
+        });
+        assert_eq!(count, tim_sort_instrumented(&mut vec.clone()).comparisons);
+    }
+
+    #[test]
+    fn test_quadratic_sorts() {
+        let len = 100;
+        let reversed: Vec<u32> = (0..len as u32).rev().collect();
+
+        // 逆序时每一对元素都要交换一次
+        let stats = bubble_sort_instrumented(&mut reversed.clone());
+        assert_eq!(len * (len - 1) / 2, stats.swaps);
+        assert_eq!(0, stats.allocations);
+
+        let stats = selection_sort_instrumented(&mut reversed.clone());
+        assert_eq!(len * (len - 1) / 2, stats.comparisons);
+        assert_eq!(len / 2, stats.swaps);
+    }
+
+    #[test]
+    fn test_small_sorts() {
+        // 排序网络的比较次数是固定的，16 个元素时是 60 次
+        let mut seed = 13;
+        let mut arr: [u32; 16] = std::array::from_fn(|_| random(&mut seed) as u32);
+        let mut expected = arr;
+        expected.sort();
+        let stats = sort_network_instrumented(&mut arr);
+        assert_eq!(expected, arr);
+        let reversed = sort_network_instrumented(&mut arr.map(|x| !x));
+        assert_eq!(60, stats.comparisons);
+        assert_eq!(stats.comparisons, reversed.comparisons);
+        assert_eq!(0, stats.allocations);
+
+        // 无分支插入排序的比较次数不超过 n·ceil(log2 n)
+        let len = 100;
+        let reversed: Vec<u32> = (0..len as u32).rev().collect();
+        let stats = branchless_insertion_sort_instrumented(&mut reversed.clone());
+        assert!(stats.comparisons <= len * 7);
+        assert_eq!(len * (len + 1) / 2 - 1, stats.moves);
+        assert_eq!(0, stats.swaps);
+    }
+
+    #[test]
+    fn test_shell_gaps() {
+        let mut seed = 17;
+        let vec: Vec<u32> = (0..10_000).map(|_| random(&mut seed) as u32).collect();
+
+        let comparisons = |gaps| shell_sort_instrumented(&mut vec.clone(), gaps).comparisons;
+        let shell = comparisons(GapSequence::Shell);
+        let knuth = comparisons(GapSequence::Knuth);
+        let sedgewick = comparisons(GapSequence::Sedgewick);
+        let ciura = comparisons(GapSequence::Ciura);
+        let tokuda = comparisons(GapSequence::Tokuda);
+
+        // 随机输入上，后来的序列比 Shell 原始的序列少得多，Ciura 和 Tokuda 最少
+        assert!(knuth < shell && sedgewick < shell);
+        assert!(ciura < knuth && ciura < sedgewick);
+        assert!(tokuda < knuth && tokuda < sedgewick);
+
+        // 插入排序的比较次数约为 n²/4，希尔排序远远少于它
+        assert!(shell < vec.len() * vec.len() / 40);
+    }
+
+    #[test]
+    fn test_depth() {
+        let len = 1 << 12;
+        let vec: Vec<u32> = (0..len).collect();
+
+        // 长度不超过 16 的区间不再拆分，少了最后 4 层
+        assert_eq!(8, merge_sort_instrumented(&mut vec.clone()).max_depth);
+        assert!(quick_sort_instrumented(&mut vec.clone()).max_depth <= 12);
+        assert_eq!(0, tim_sort_instrumented(&mut vec.clone()).max_depth);
+        assert_eq!(0, heap_sort_instrumented(&mut vec.clone()).max_depth);
+
+        // 非递归版本用显式的栈或按宽度循环，深度为 0
+        assert_eq!(
+            0,
+            quick_sort_iterative_instrumented(&mut vec.clone()).max_depth
+        );
+        assert_eq!(
+            0,
+            merge_sort_bottom_up_instrumented(&mut vec.clone()).max_depth
+        );
+
+        // 原地合并只对较短的一侧递归，深度不超过 log2(n)
+        let reversed: Vec<u32> = (0..len).rev().collect();
+        let stats = merge_sort_in_place_instrumented(&mut reversed.clone());
+        assert!(stats.max_depth <= 12);
+        assert_eq!(0, stats.allocations);
+
+        // 并行版本的深度由各个线程汇总而来，与顺序版本相同
+        let vec: Vec<u32> = (0..1 << 15).rev().collect();
+        let seq = merge_sort_instrumented(&mut vec.clone());
+        let par = par_merge_sort_instrumented(&mut vec.clone(), 8);
+        assert_eq!(seq.max_depth, par.max_depth);
+        assert_eq!(seq.moves, par.moves);
+        // 顺序版本只分配一次，并行版本每个线程和每次并行合并都要分配
+        assert_eq!(1, seq.allocations);
+        assert!(par.allocations > 1);
+    }
+}
@@ -0,0 +1,249 @@
+use super::{choose_pivot, depth_limit, heap, less_at, Probe};
+use std::cmp::Ordering;
+
+/// # 三路快速排序
+///
+/// 又称荷兰国旗问题的解法：分区时把数组分成小于、等于、大于 pivot 的三段，
+/// 只对小于和大于的两段继续递归，等于 pivot 的元素一次就位。
+///
+/// 大量重复元素时比 [`quick_sort`](super::quick_sort) 少做很多比较，
+/// 全部元素相等时只需一轮分区。不稳定。
+///
+/// ```
+/// # use algorithm_study::sorting::quick_sort_3way;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![1];
+/// let mut vec_2 = vec![3,5,2,4,1];
+/// let mut vec_3 = vec![2,1,2,3,1,2,3,1,2];
+///
+/// quick_sort_3way(&mut vec_0);
+/// quick_sort_3way(&mut vec_1);
+/// quick_sort_3way(&mut vec_2);
+/// quick_sort_3way(&mut vec_3);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1]);
+/// assert_eq!(vec_2, vec![1,2,3,4,5]);
+/// assert_eq!(vec_3, vec![1,1,1,2,2,2,2,3,3]);
+/// ```
+///
+pub fn quick_sort_3way<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    quick_sort_3way_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # 三路快速排序（自定义比较）
+///
+/// 与 [`quick_sort_3way`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::quick_sort_3way_by;
+/// #
+/// let mut vec = vec![3,5,3,4,5];
+///
+/// quick_sort_3way_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,5,4,3,3]);
+/// ```
+///
+pub fn quick_sort_3way_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quick_sort_3way_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # 三路快速排序（按键排序）
+///
+/// 与 [`quick_sort_3way`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::quick_sort_3way_by_key;
+/// #
+/// let mut vec = vec![(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+///
+/// quick_sort_3way_by_key(&mut vec, |x| x.0);
+///
+/// assert!(vec[..2].iter().all(|x| x.0 == 1));
+/// assert!(vec[2..].iter().all(|x| x.0 == 3));
+/// ```
+///
+pub fn quick_sort_3way_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    quick_sort_3way_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+pub(super) fn quick_sort_3way_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    quick_sort_3way_with_idx(vec, 0, vec.len(), depth_limit(vec.len()), is_less, probe);
+
+    fn quick_sort_3way_with_idx<T, F, P>(
+        vec: &mut [T],
+        mut start: usize,
+        mut end: usize,
+        mut limit: u32,
+        is_less: &mut F,
+        probe: &P,
+    ) where
+        F: FnMut(&T, &T) -> bool,
+        P: Probe,
+    {
+        while start + 1 < end {
+            if limit == 0 {
+                probe.shift(start);
+                heap::heap_sort_impl(&mut vec[start..end], is_less, probe);
+                probe.unshift(start);
+                return;
+            }
+            limit -= 1;
+
+            let (lt, gt) = partition_3way(vec, start, end, is_less, probe);
+
+            // 只对较短的一侧递归，较长的一侧继续循环
+            probe.enter();
+            if lt - start < end - gt {
+                quick_sort_3way_with_idx(vec, start, lt, limit, is_less, probe);
+                start = gt;
+            } else {
+                quick_sort_3way_with_idx(vec, gt, end, limit, is_less, probe);
+                end = lt;
+            }
+            probe.leave();
+        }
+    }
+}
+
+/// 三路分区，返回 `(lt, gt)`。
+///
+/// 分区后 `vec[start..lt]` 小于 pivot，`vec[lt..gt]` 等于 pivot，`vec[gt..end]` 大于 pivot。
+pub(crate) fn partition_3way<T, F, P>(
+    vec: &mut [T],
+    start: usize,
+    end: usize,
+    is_less: &mut F,
+    probe: &P,
+) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let pivot = choose_pivot(vec, start, end, is_less, probe);
+    probe.partition_start(start, end, pivot);
+    vec.swap(start, pivot);
+    probe.swap(start, pivot);
+
+    // [start, lt) 小于 pivot，[lt, i) 等于 pivot，[i, gt) 未处理，[gt, end) 大于 pivot
+    // vec[lt] 始终是一个等于 pivot 的元素，用它来比较
+    let mut lt = start;
+    let mut i = start + 1;
+    let mut gt = end;
+
+    while i < gt {
+        if less_at(vec, i, lt, is_less, probe) {
+            // 小于：和等于区的第一个元素交换，两个边界都右移
+            vec.swap(lt, i);
+            probe.swap(lt, i);
+            lt += 1;
+            i += 1;
+        } else if less_at(vec, lt, i, is_less, probe) {
+            // 大于：换到末尾，换过来的元素还没处理，i 不动
+            gt -= 1;
+            vec.swap(i, gt);
+            probe.swap(i, gt);
+        } else {
+            // 等于：留在原地
+            i += 1;
+        }
+    }
+
+    (lt, gt)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::quick_sort_by;
+
+    #[test]
+    fn test_partition() {
+        let mut vec = vec![3, 1, 3, 5, 3, 0, 9, 3, 2];
+        let len = vec.len();
+        let (lt, gt) = partition_3way(&mut vec, 0, len, &mut |a: &i32, b: &i32| a < b, &());
+
+        assert_eq!((3, 7), (lt, gt));
+        assert!(vec[..lt].iter().all(|&x| x < 3));
+        assert!(vec[lt..gt].iter().all(|&x| x == 3));
+        assert!(vec[gt..].iter().all(|&x| x > 3));
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let len = 10_000;
+        let patterns: Vec<Vec<u32>> = vec![
+            vec![42; len],
+            (0..len as u32).map(|i| i % 2).collect(),
+            (0..len as u32).map(|i| (i * 7919) % 5).collect(),
+            (0..len as u32).map(|i| (i * 31) % 100).rev().collect(),
+        ];
+
+        for vec in patterns {
+            let mut expected = vec.clone();
+            expected.sort();
+
+            let mut sorted = vec.clone();
+            quick_sort_3way(&mut sorted);
+            assert_eq!(expected, sorted);
+        }
+    }
+
+    #[test]
+    fn test_non_copy() {
+        let mut vec: Vec<String> = (0..500).map(|i| (i % 7).to_string()).collect();
+        let mut expected = vec.clone();
+        expected.sort();
+
+        quick_sort_3way(&mut vec);
+        assert_eq!(expected, vec);
+    }
+
+    #[test]
+    fn test_fewer_comparisons() {
+        // 只有几个不同的键时，三路分区的比较次数明显少于二路分区
+        let vec: Vec<u32> = (0..10_000).map(|i| (i * 7919) % 4).collect();
+
+        let mut count_3way = 0;
+        quick_sort_3way_by(&mut vec.clone(), |a, b| {
+            count_3way += 1;
+            a.cmp(b)
+        });
+
+        let mut count_2way = 0;
+        quick_sort_by(&mut vec.clone(), |a, b| {
+            count_2way += 1;
+            a.cmp(b)
+        });
+
+        assert!(count_3way * 3 < count_2way);
+
+        // 全部相等时只需一轮分区
+        let mut count = 0;
+        quick_sort_3way_by(&mut vec![1; 10_000], |a, b| {
+            count += 1;
+            a.cmp(b)
+        });
+        assert!(count < 2 * 10_000 + 20);
+    }
+}
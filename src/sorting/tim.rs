@@ -0,0 +1,714 @@
+use super::{MergeHole, Probe};
+use std::cmp::Ordering;
+use std::ptr;
+use std::slice;
+
+/// 连续赢这么多次之后进入飞奔模式
+const MIN_GALLOP: usize = 7;
+
+/// # TimSort
+///
+/// 自适应的归并排序，充分利用输入中已经有序的片段（run）：
+///
+/// - 从左到右找出连续不降或严格下降的 run，下降的 run 原地翻转；
+/// - 太短的 run 用二分插入排序补到 `minrun` 的长度（32 ~ 64）；
+/// - run 压入栈中，按长度约束及时合并，保证合并是平衡的；
+/// - 合并时若某一侧连续胜出，改用指数搜索整段搬移（飞奔模式）。
+///
+/// 整个排序只分配一块长度为 n/2 的辅助空间。基本有序的输入接近 O(n)，
+/// 最坏 O(n log n)，排序是稳定的。
+///
+/// ```
+/// # use algorithm_study::sorting::tim_sort;
+/// #
+/// let mut vec_0: Vec<i32> = Vec::new();
+/// let mut vec_1 = vec![1];
+/// let mut vec_2 = vec![3,5,2,4,1];
+/// let mut vec_3: Vec<i32> = (0..100).chain((0..100).rev()).collect();
+///
+/// tim_sort(&mut vec_0);
+/// tim_sort(&mut vec_1);
+/// tim_sort(&mut vec_2);
+/// tim_sort(&mut vec_3);
+///
+/// assert_eq!(vec_0, Vec::new());
+/// assert_eq!(vec_1, vec![1]);
+/// assert_eq!(vec_2, vec![1,2,3,4,5]);
+/// assert_eq!(vec_3, (0..200).map(|i| i / 2).collect::<Vec<_>>());
+/// ```
+///
+pub fn tim_sort<T>(vec: &mut [T])
+where
+    T: Ord,
+{
+    tim_sort_impl(vec, &mut |a: &T, b: &T| a < b, &());
+}
+
+/// # TimSort（自定义比较）
+///
+/// 与 [`tim_sort`] 相同，但使用 `compare` 决定元素顺序。
+///
+/// ```
+/// # use algorithm_study::sorting::tim_sort_by;
+/// #
+/// let mut vec = vec![3,5,2,4,1];
+///
+/// tim_sort_by(&mut vec, |a, b| b.cmp(a));
+///
+/// assert_eq!(vec, vec![5,4,3,2,1]);
+/// ```
+///
+pub fn tim_sort_by<T, F>(vec: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    tim_sort_impl(
+        vec,
+        &mut |a: &T, b: &T| compare(a, b) == Ordering::Less,
+        &(),
+    );
+}
+
+/// # TimSort（按键排序）
+///
+/// 与 [`tim_sort`] 相同，但按 `f` 提取的键排序。
+///
+/// ```
+/// # use algorithm_study::sorting::tim_sort_by_key;
+/// #
+/// let mut vec = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+///
+/// tim_sort_by_key(&mut vec, |x| x.0);
+///
+/// assert_eq!(vec, vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+/// ```
+///
+pub fn tim_sort_by_key<T, K, F>(vec: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    tim_sort_impl(vec, &mut |a: &T, b: &T| f(a) < f(b), &());
+}
+
+/// 栈中的一个 run：`vec[start..start + len]` 已经有序
+#[derive(Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+pub(super) fn tim_sort_impl<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let len = vec.len();
+
+    if len < 2 {
+        return;
+    }
+
+    let min_run = min_run_len(len);
+    let mut buf = Vec::with_capacity(len / 2);
+    probe.alloc(len / 2);
+    let mut min_gallop = MIN_GALLOP;
+    let mut runs: Vec<Run> = Vec::new();
+    let mut start = 0;
+
+    while start < len {
+        // 找出下一个 run，太短就用插入排序补长
+        let mut run_len = find_run(&mut vec[start..], is_less, probe);
+        if run_len < min_run {
+            let forced = min_run.min(len - start);
+            binary_insertion_sort(&mut vec[start..start + forced], run_len, is_less, probe);
+            run_len = forced;
+        }
+
+        runs.push(Run {
+            start,
+            len: run_len,
+        });
+        start += run_len;
+
+        // 维持栈的长度约束，最后一个 run 到达末尾时合并全部
+        while let Some(r) = collapse(&runs, len) {
+            let (left, right) = (runs[r], runs[r + 1]);
+            let range = &mut vec[left.start..right.start + right.len];
+            merge_runs(range, left.len, &mut buf, &mut min_gallop, is_less, probe);
+
+            runs[r].len += right.len;
+            runs.remove(r + 1);
+        }
+    }
+}
+
+/// 计算 minrun：取 n 的最高 6 位，若其余位中有 1 则再加 1。
+///
+/// 这样 n / minrun 恰好是或略小于 2 的幂，最后的合并比较平衡。
+fn min_run_len(mut len: usize) -> usize {
+    let mut rest = 0;
+
+    while len >= 64 {
+        rest |= len & 1;
+        len >>= 1;
+    }
+
+    len + rest
+}
+
+/// 返回从头开始的 run 的长度，严格下降的 run 会被原地翻转。
+///
+/// 只翻转严格下降的 run，相等的元素不会被翻转，保证稳定。
+fn find_run<T, F, P>(vec: &mut [T], is_less: &mut F, probe: &P) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    let len = vec.len();
+
+    if len < 2 {
+        return len;
+    }
+
+    let mut end = 2;
+    if is_less(&vec[1], &vec[0]) {
+        while end < len && is_less(&vec[end], &vec[end - 1]) {
+            end += 1;
+        }
+        for i in 0..end / 2 {
+            vec.swap(i, end - 1 - i);
+            probe.swap(i, end - 1 - i);
+        }
+    } else {
+        while end < len && !is_less(&vec[end], &vec[end - 1]) {
+            end += 1;
+        }
+    }
+
+    end
+}
+
+/// 二分插入排序，`vec[..sorted]` 已经有序。
+///
+/// 二分查找插入位置时落在相等元素之后，保证稳定。
+fn binary_insertion_sort<T, F, P>(vec: &mut [T], sorted: usize, is_less: &mut F, probe: &P)
+where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    for i in sorted.max(1)..vec.len() {
+        let (mut lo, mut hi) = (0, i);
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if is_less(&vec[i], &vec[mid]) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+
+        if lo < i {
+            vec[lo..=i].rotate_right(1);
+            probe.insert(i, lo);
+        }
+    }
+}
+
+/// 若栈顶的 run 需要合并，返回要合并的相邻两个 run 中左边那个的下标。
+///
+/// 对栈顶的 W、X、Y、Z（Z 在最上面）要求 `Y > Z`、`X > Y + Z`、`W > X + Y`，
+/// 保证栈中 run 的长度至少按斐波那契数列增长，栈的深度为 O(log n)。
+fn collapse(runs: &[Run], stop: usize) -> Option<usize> {
+    let n = runs.len();
+
+    if n >= 2
+        && (runs[n - 1].start + runs[n - 1].len == stop
+            || runs[n - 2].len <= runs[n - 1].len
+            || (n >= 3 && runs[n - 3].len <= runs[n - 2].len + runs[n - 1].len)
+            || (n >= 4 && runs[n - 4].len <= runs[n - 3].len + runs[n - 2].len))
+    {
+        if n >= 3 && runs[n - 3].len < runs[n - 1].len {
+            Some(n - 3)
+        } else {
+            Some(n - 2)
+        }
+    } else {
+        None
+    }
+}
+
+/// 合并 `vec[..mid]` 与 `vec[mid..]` 两个相邻的 run。
+fn merge_runs<T, F, P>(
+    vec: &mut [T],
+    mid: usize,
+    buf: &mut Vec<T>,
+    min_gallop: &mut usize,
+    is_less: &mut F,
+    probe: &P,
+) where
+    F: FnMut(&T, &T) -> bool,
+    P: Probe,
+{
+    // 左段中不大于右段第一个元素的前缀已经就位
+    let skip = gallop_front(&vec[..mid], &mut |x| !is_less(&vec[mid], x));
+    if skip == mid {
+        return;
+    }
+
+    // 右段中不小于左段最后一个元素的后缀已经就位
+    let keep = gallop_back(&vec[mid..], &mut |x| !is_less(x, &vec[mid - 1]));
+
+    let end = vec.len() - keep;
+    let vec = &mut vec[skip..end];
+    let mid = mid - skip;
+
+    let short = mid.min(vec.len() - mid);
+    buf.clear();
+    buf.reserve(short);
+
+    // SAFETY: buf 的容量足够放下较短的一段
+    let moved = unsafe {
+        if mid <= vec.len() - mid {
+            merge_lo(vec, mid, buf.as_mut_ptr(), min_gallop, is_less)
+        } else {
+            merge_hi(vec, mid, buf.as_mut_ptr(), min_gallop, is_less)
+        }
+    };
+
+    // 较短一段移入 buf 再移回，各算一次移动
+    probe.moves(2 * short + moved);
+}
+
+/// 左段较短：移入 buf，从前往后合并，返回右段中被移动的元素个数。
+///
+/// # Safety
+///
+/// `buf` 至少能放下 `mid` 个元素，且不与 `vec` 重叠。
+unsafe fn merge_lo<T, F>(
+    vec: &mut [T],
+    mid: usize,
+    buf: *mut T,
+    min_gallop: &mut usize,
+    is_less: &mut F,
+) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let v = vec.as_mut_ptr();
+    let v_end = v.add(vec.len());
+
+    ptr::copy_nonoverlapping(v, buf, mid);
+    let mut hole = MergeHole {
+        start: buf,
+        end: buf.add(mid),
+        dest: v,
+    };
+    let mut right = v.add(mid);
+
+    // 空位 [dest, right) 的长度始终等于 buf 中剩余元素的个数
+    'outer: loop {
+        let mut left_wins = 0;
+        let mut right_wins = 0;
+
+        // 逐个比较，右段严格更小时才取右段
+        loop {
+            if is_less(&*right, &*hole.start) {
+                ptr::copy_nonoverlapping(right, hole.dest, 1);
+                right = right.add(1);
+                right_wins += 1;
+                left_wins = 0;
+            } else {
+                ptr::copy_nonoverlapping(hole.start, hole.dest, 1);
+                hole.start = hole.start.add(1);
+                left_wins += 1;
+                right_wins = 0;
+            }
+            hole.dest = hole.dest.add(1);
+
+            if hole.start == hole.end || right == v_end {
+                break 'outer;
+            }
+            if left_wins.max(right_wins) >= *min_gallop {
+                break;
+            }
+        }
+
+        // 飞奔模式：用指数搜索找出可以整段搬移的元素
+        loop {
+            // buf 中不大于右段头部的元素
+            let left = slice::from_raw_parts(hole.start, hole.end.offset_from(hole.start) as usize);
+            let left_count = gallop_front(left, &mut |x| !is_less(&*right, x));
+            ptr::copy_nonoverlapping(hole.start, hole.dest, left_count);
+            hole.start = hole.start.add(left_count);
+            hole.dest = hole.dest.add(left_count);
+            if hole.start == hole.end {
+                break 'outer;
+            }
+
+            // 此时右段头部一定更小
+            ptr::copy_nonoverlapping(right, hole.dest, 1);
+            right = right.add(1);
+            hole.dest = hole.dest.add(1);
+            if right == v_end {
+                break 'outer;
+            }
+
+            // 右段中小于 buf 头部的元素，可能与空位重叠
+            let rest = slice::from_raw_parts(right, v_end.offset_from(right) as usize);
+            let right_count = gallop_front(rest, &mut |x| is_less(x, &*hole.start));
+            ptr::copy(right, hole.dest, right_count);
+            right = right.add(right_count);
+            hole.dest = hole.dest.add(right_count);
+            if right == v_end {
+                break 'outer;
+            }
+
+            // 此时 buf 头部一定不大于右段头部
+            ptr::copy_nonoverlapping(hole.start, hole.dest, 1);
+            hole.start = hole.start.add(1);
+            hole.dest = hole.dest.add(1);
+            if hole.start == hole.end {
+                break 'outer;
+            }
+
+            // 飞奔效果不好就退回逐个比较，并提高下次进入飞奔的门槛
+            if left_count < MIN_GALLOP && right_count < MIN_GALLOP {
+                *min_gallop += 2;
+                break;
+            }
+            *min_gallop = min_gallop.saturating_sub(1).max(1);
+        }
+    }
+
+    // hole 在 drop 时把 buf 中剩余的元素移回空位
+    right.offset_from(v.add(mid)) as usize
+}
+
+/// 右段较短：移入 buf，从后往前合并，返回左段中被移动的元素个数。
+///
+/// # Safety
+///
+/// `buf` 至少能放下 `vec.len() - mid` 个元素，且不与 `vec` 重叠。
+unsafe fn merge_hi<T, F>(
+    vec: &mut [T],
+    mid: usize,
+    buf: *mut T,
+    min_gallop: &mut usize,
+    is_less: &mut F,
+) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let v = vec.as_mut_ptr();
+    let len = vec.len();
+
+    ptr::copy_nonoverlapping(v.add(mid), buf, len - mid);
+    // dest 是左段剩余部分的末尾，空位 [dest, out) 的长度始终等于 buf 中剩余元素的个数
+    let mut hole = MergeHole {
+        start: buf,
+        end: buf.add(len - mid),
+        dest: v.add(mid),
+    };
+    let mut out = v.add(len);
+
+    'outer: loop {
+        let mut left_wins = 0;
+        let mut right_wins = 0;
+
+        // 从尾部逐个比较，左段严格更大时才取左段
+        loop {
+            out = out.sub(1);
+            if is_less(&*hole.end.sub(1), &*hole.dest.sub(1)) {
+                hole.dest = hole.dest.sub(1);
+                ptr::copy_nonoverlapping(hole.dest, out, 1);
+                left_wins += 1;
+                right_wins = 0;
+            } else {
+                hole.end = hole.end.sub(1);
+                ptr::copy_nonoverlapping(hole.end, out, 1);
+                right_wins += 1;
+                left_wins = 0;
+            }
+
+            if hole.start == hole.end || hole.dest == v {
+                break 'outer;
+            }
+            if left_wins.max(right_wins) >= *min_gallop {
+                break;
+            }
+        }
+
+        loop {
+            // 左段尾部严格大于 buf 末尾元素的部分，可能与空位重叠
+            let left = slice::from_raw_parts(v, hole.dest.offset_from(v) as usize);
+            let left_count = gallop_back(left, &mut |x| is_less(&*hole.end.sub(1), x));
+            hole.dest = hole.dest.sub(left_count);
+            out = out.sub(left_count);
+            ptr::copy(hole.dest, out, left_count);
+            if hole.dest == v {
+                break 'outer;
+            }
+
+            // 此时 buf 末尾一定不小于左段末尾
+            hole.end = hole.end.sub(1);
+            out = out.sub(1);
+            ptr::copy_nonoverlapping(hole.end, out, 1);
+            if hole.start == hole.end {
+                break 'outer;
+            }
+
+            // buf 尾部不小于左段末尾的部分
+            let right =
+                slice::from_raw_parts(hole.start, hole.end.offset_from(hole.start) as usize);
+            let right_count = gallop_back(right, &mut |x| !is_less(x, &*hole.dest.sub(1)));
+            hole.end = hole.end.sub(right_count);
+            out = out.sub(right_count);
+            ptr::copy_nonoverlapping(hole.end, out, right_count);
+            if hole.start == hole.end {
+                break 'outer;
+            }
+
+            // 此时左段末尾一定严格更大
+            hole.dest = hole.dest.sub(1);
+            out = out.sub(1);
+            ptr::copy_nonoverlapping(hole.dest, out, 1);
+            if hole.dest == v {
+                break 'outer;
+            }
+
+            if left_count < MIN_GALLOP && right_count < MIN_GALLOP {
+                *min_gallop += 2;
+                break;
+            }
+            *min_gallop = min_gallop.saturating_sub(1).max(1);
+        }
+    }
+
+    v.add(mid).offset_from(hole.dest) as usize
+}
+
+/// `pred` 对 `vec` 的一个前缀成立、对其余元素不成立，返回该前缀的长度。
+///
+/// 先以 1、3、7、15…… 的步长指数式向后试探，再在最后一段里二分查找，
+/// 前缀较短时只需 O(log k) 次比较。
+fn gallop_front<T, P>(vec: &[T], pred: &mut P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    let mut lo = 0;
+    let mut step = 1;
+
+    // 结果落在 [lo, hi] 之间
+    let hi = loop {
+        let probe = lo + step - 1;
+        if probe >= vec.len() {
+            break vec.len();
+        }
+        if !pred(&vec[probe]) {
+            break probe;
+        }
+        lo = probe + 1;
+        step *= 2;
+    };
+
+    let mut hi = hi;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(&vec[mid]) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    lo
+}
+
+/// `pred` 对 `vec` 的一个后缀成立、对其余元素不成立，返回该后缀的长度。
+fn gallop_back<T, P>(vec: &[T], pred: &mut P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    let len = vec.len();
+    let mut lo = 0;
+    let mut step = 1;
+
+    // 后缀长度落在 [lo, hi] 之间
+    let hi = loop {
+        let probe = lo + step - 1;
+        if probe >= len {
+            break len;
+        }
+        if !pred(&vec[len - 1 - probe]) {
+            break probe;
+        }
+        lo = probe + 1;
+        step *= 2;
+    };
+
+    let mut hi = hi;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(&vec[len - 1 - mid]) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    lo
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::random;
+
+    fn check_stable(vec: Vec<u32>) {
+        let vec: Vec<(u32, usize)> = vec.into_iter().enumerate().map(|(i, x)| (x, i)).collect();
+        let mut expected = vec.clone();
+        expected.sort_by_key(|x| x.0);
+
+        let mut sorted = vec.clone();
+        tim_sort_by_key(&mut sorted, |x| x.0);
+        assert_eq!(expected, sorted);
+    }
+
+    #[test]
+    fn test_min_run_len() {
+        assert_eq!(1, min_run_len(1));
+        assert_eq!(63, min_run_len(63));
+        assert_eq!(32, min_run_len(64));
+        assert_eq!(33, min_run_len(65));
+        assert_eq!(32, min_run_len(1 << 20));
+        assert_eq!(33, min_run_len((1 << 20) + 1));
+    }
+
+    #[test]
+    fn test_gallop() {
+        let vec = [1, 2, 2, 3, 5, 8, 8, 9];
+        for key in 0..10 {
+            assert_eq!(
+                vec.iter().filter(|&&x| x <= key).count(),
+                gallop_front(&vec, &mut |&x| x <= key)
+            );
+            assert_eq!(
+                vec.iter().filter(|&&x| x > key).count(),
+                gallop_back(&vec, &mut |&x| x > key)
+            );
+        }
+    }
+
+    #[test]
+    fn test_random() {
+        let mut seed = 1;
+
+        for len in [2, 3, 63, 64, 65, 500, 4096, 10_000] {
+            for range in [2, 100, u64::MAX] {
+                check_stable(
+                    (0..len)
+                        .map(|_| (random(&mut seed) % range) as u32)
+                        .collect(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_runs() {
+        let mut seed = 2;
+        let len = 10_000;
+
+        // 多个有序片段，交替区间大量重叠或完全错开，触发飞奔模式
+        let patterns: Vec<Vec<u32>> = vec![
+            (0..len).collect(),
+            (0..len).rev().collect(),
+            (0..len).map(|i| i % 1000).collect(),
+            (0..len).map(|i| (i % 1000) * 10 + i / 1000).collect(),
+            (0..len)
+                .map(|i| {
+                    if i < len / 2 {
+                        i * 2
+                    } else {
+                        (i - len / 2) * 2 + 1
+                    }
+                })
+                .collect(),
+            (0..len)
+                .map(|i| {
+                    if i % 500 == 0 {
+                        random(&mut seed) as u32
+                    } else {
+                        i
+                    }
+                })
+                .collect(),
+            (0..len).map(|i| (i / 100) % 3).collect(),
+        ];
+
+        for vec in patterns {
+            check_stable(vec);
+        }
+    }
+
+    #[test]
+    fn test_nearly_sorted_comparisons() {
+        let len = 100_000;
+        let mut vec: Vec<u32> = (0..len).collect();
+        vec.swap(10, 20_000);
+        vec.swap(50_000, 99_999);
+
+        let mut count = 0;
+        tim_sort_by(&mut vec, |a, b| {
+            count += 1;
+            a.cmp(b)
+        });
+
+        assert_eq!((0..len).collect::<Vec<_>>(), vec);
+        assert!(count < 3 * len as usize);
+    }
+
+    #[test]
+    fn test_non_copy() {
+        let mut seed = 5;
+        let mut vec: Vec<String> = (0..3000)
+            .map(|_| (random(&mut seed) % 97).to_string())
+            .collect();
+        let mut expected = vec.clone();
+        expected.sort();
+
+        tim_sort(&mut vec);
+        assert_eq!(expected, vec);
+    }
+
+    #[test]
+    fn test_panic_safety() {
+        let mut seed = 9;
+        let vec: Vec<String> = (0..2000)
+            .map(|_| (random(&mut seed) % 97).to_string())
+            .collect();
+        let mut expected = vec.clone();
+        expected.sort();
+
+        for limit in [10, 1000, 5000, 15_000] {
+            let mut sorted = vec.clone();
+            let mut count = 0;
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                tim_sort_by(&mut sorted, |a, b| {
+                    count += 1;
+                    if count == limit {
+                        panic!("comparison limit reached");
+                    }
+                    a.cmp(b)
+                })
+            }));
+            assert!(result.is_err());
+
+            // panic 之后元素既不丢失也不重复
+            sorted.sort();
+            assert_eq!(expected, sorted);
+        }
+    }
+}
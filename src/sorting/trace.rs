@@ -0,0 +1,485 @@
+use super::probe::Probe;
+use super::{
+    bubble_sort_impl, insertion_sort_impl, merge_sort_impl, quick_sort_impl, selection_sort_impl,
+};
+use std::cell::{Cell, RefCell};
+
+/// # 排序事件
+///
+/// `*_traced` 系列函数在排序过程中依次产生的事件，下标都是在整个数组中的下标。
+/// 按顺序把事件作用到输入的副本上（见 [`replay_trace`]），就能还原排序的每一步：
+///
+/// - `Compare(i, j)`：比较了 `vec[i]` 与 `vec[j]`；
+/// - `Swap(i, j)`：交换了 `vec[i]` 与 `vec[j]`；
+/// - `Insert { from, to }`：把 `vec[from]` 插入到 `to`，`vec[to..from]` 整体右移一位；
+/// - `MergeRange { start, mid, end }`：开始合并 `vec[start..mid]` 与 `vec[mid..end]`；
+/// - `Write { to, from }`：合并时，把合并开始时位于 `from` 的元素写到 `to`；
+/// - `PartitionStart { start, end, pivot }`：开始对 `vec[start..end]` 分区，以 `vec[pivot]` 为分区点。
+///
+/// 合并时较短的一段暂存在辅助空间里，这期间 `Compare` 的下标也是元素在合并开始时的下标。
+///
+/// ```
+/// # use algorithm_study::sorting::{bubble_sort_traced, SortEvent};
+/// #
+/// let mut vec = vec![2,1];
+/// let mut events = Vec::new();
+///
+/// bubble_sort_traced(&mut vec, |e| events.push(e));
+///
+/// assert_eq!(vec, vec![1,2]);
+/// assert_eq!(events, vec![SortEvent::Compare(1, 0), SortEvent::Swap(0, 1)]);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortEvent {
+    Compare(usize, usize),
+    Swap(usize, usize),
+    Insert {
+        from: usize,
+        to: usize,
+    },
+    MergeRange {
+        start: usize,
+        mid: usize,
+        end: usize,
+    },
+    Write {
+        to: usize,
+        from: usize,
+    },
+    PartitionStart {
+        start: usize,
+        end: usize,
+        pivot: usize,
+    },
+}
+
+/// 把事件交给回调的探针，`base` 是当前子切片在整个数组中的起点
+struct Tracer<F> {
+    on_event: RefCell<F>,
+    base: Cell<usize>,
+}
+
+impl<F: FnMut(SortEvent)> Tracer<F> {
+    fn new(on_event: F) -> Self {
+        Tracer {
+            on_event: RefCell::new(on_event),
+            base: Cell::new(0),
+        }
+    }
+
+    fn emit(&self, event: SortEvent) {
+        (self.on_event.borrow_mut())(event);
+    }
+}
+
+impl<F: FnMut(SortEvent)> Probe for Tracer<F> {
+    fn compare_at(&self, i: usize, j: usize) {
+        let b = self.base.get();
+        self.emit(SortEvent::Compare(b + i, b + j));
+    }
+
+    fn swap(&self, i: usize, j: usize) {
+        let b = self.base.get();
+        self.emit(SortEvent::Swap(b + i, b + j));
+    }
+
+    fn insert(&self, from: usize, to: usize) {
+        let b = self.base.get();
+        self.emit(SortEvent::Insert {
+            from: b + from,
+            to: b + to,
+        });
+    }
+
+    fn merge_range(&self, start: usize, mid: usize, end: usize) {
+        let b = self.base.get();
+        self.emit(SortEvent::MergeRange {
+            start: b + start,
+            mid: b + mid,
+            end: b + end,
+        });
+    }
+
+    fn write(&self, to: usize, from: usize) {
+        let b = self.base.get();
+        self.emit(SortEvent::Write {
+            to: b + to,
+            from: b + from,
+        });
+    }
+
+    fn partition_start(&self, start: usize, end: usize, pivot: usize) {
+        let b = self.base.get();
+        self.emit(SortEvent::PartitionStart {
+            start: b + start,
+            end: b + end,
+            pivot: b + pivot,
+        });
+    }
+
+    fn shift(&self, start: usize) {
+        self.base.set(self.base.get() + start);
+    }
+
+    fn unshift(&self, start: usize) {
+        self.base.set(self.base.get() - start);
+    }
+}
+
+/// # 冒泡排序（跟踪）
+///
+/// 与 [`bubble_sort`](super::bubble_sort) 相同，排序过程中的每一步都以 [`SortEvent`] 交给 `on_event`。
+///
+/// ```
+/// # use algorithm_study::sorting::{bubble_sort_traced, SortEvent};
+/// #
+/// let mut vec = vec![3,1,2];
+/// let mut swaps = Vec::new();
+///
+/// bubble_sort_traced(&mut vec, |e| {
+///     if let SortEvent::Swap(i, j) = e {
+///         swaps.push((i, j));
+///     }
+/// });
+///
+/// assert_eq!(vec, vec![1,2,3]);
+/// assert_eq!(swaps, vec![(0, 1), (1, 2)]);
+/// ```
+///
+pub fn bubble_sort_traced<T, F>(vec: &mut [T], on_event: F)
+where
+    T: Ord,
+    F: FnMut(SortEvent),
+{
+    let tracer = Tracer::new(on_event);
+    bubble_sort_impl(vec, &mut |a: &T, b: &T| a < b, &tracer);
+}
+
+/// # 插入排序（跟踪）
+///
+/// 与 [`insertion_sort`](super::insertion_sort) 相同，排序过程中的每一步都以 [`SortEvent`] 交给 `on_event`。
+///
+/// ```
+/// # use algorithm_study::sorting::{insertion_sort_traced, SortEvent};
+/// #
+/// let mut vec = vec![2,3,1];
+/// let mut events = Vec::new();
+///
+/// insertion_sort_traced(&mut vec, |e| events.push(e));
+///
+/// assert_eq!(vec, vec![1,2,3]);
+/// assert_eq!(
+///     events,
+///     vec![
+///         SortEvent::Compare(1, 0),
+///         SortEvent::Compare(2, 1),
+///         SortEvent::Compare(2, 0),
+///         SortEvent::Insert { from: 2, to: 0 },
+///     ]
+/// );
+/// ```
+///
+pub fn insertion_sort_traced<T, F>(vec: &mut [T], on_event: F)
+where
+    T: Ord,
+    F: FnMut(SortEvent),
+{
+    let tracer = Tracer::new(on_event);
+    insertion_sort_impl(vec, &mut |a: &T, b: &T| a < b, &tracer);
+}
+
+/// # 选择排序（跟踪）
+///
+/// 与 [`selection_sort`](super::selection_sort) 相同，排序过程中的每一步都以 [`SortEvent`] 交给 `on_event`。
+///
+/// ```
+/// # use algorithm_study::sorting::{selection_sort_traced, SortEvent};
+/// #
+/// let mut vec = vec![3,2,1];
+/// let mut events = Vec::new();
+///
+/// selection_sort_traced(&mut vec, |e| events.push(e));
+///
+/// assert_eq!(vec, vec![1,2,3]);
+/// assert_eq!(events.iter().filter(|e| matches!(e, SortEvent::Compare(..))).count(), 3);
+/// // 第一轮找到最小的 1，与最前面的 3 交换后就已经有序了
+/// assert_eq!(events[2], SortEvent::Swap(2, 0));
+/// assert_eq!(events.iter().filter(|e| matches!(e, SortEvent::Swap(..))).count(), 1);
+/// ```
+///
+pub fn selection_sort_traced<T, F>(vec: &mut [T], on_event: F)
+where
+    T: Ord,
+    F: FnMut(SortEvent),
+{
+    let tracer = Tracer::new(on_event);
+    selection_sort_impl(vec, &mut |a: &T, b: &T| a < b, &tracer);
+}
+
+/// # 归并排序（跟踪）
+///
+/// 与 [`merge_sort_slice`](super::merge_sort_slice) 相同，排序过程中的每一步都以 [`SortEvent`] 交给 `on_event`。
+///
+/// ```
+/// # use algorithm_study::sorting::{merge_sort_traced, SortEvent};
+/// #
+/// let mut vec: Vec<i32> = (0..64).rev().collect();
+/// let mut merges = Vec::new();
+///
+/// merge_sort_traced(&mut vec, |e| {
+///     if let SortEvent::MergeRange { start, mid, end } = e {
+///         merges.push((start, mid, end));
+///     }
+/// });
+///
+/// // 长度为 16 的区间用插入排序，不再拆分
+/// assert_eq!(vec, (0..64).collect::<Vec<_>>());
+/// assert_eq!(merges, vec![(0, 16, 32), (32, 48, 64), (0, 32, 64)]);
+/// ```
+///
+pub fn merge_sort_traced<T, F>(vec: &mut [T], on_event: F)
+where
+    T: Ord,
+    F: FnMut(SortEvent),
+{
+    let tracer = Tracer::new(on_event);
+    merge_sort_impl(vec, &mut |a: &T, b: &T| a < b, &tracer);
+}
+
+/// # 快速排序（跟踪）
+///
+/// 与 [`quick_sort`](super::quick_sort) 相同，排序过程中的每一步都以 [`SortEvent`] 交给 `on_event`。
+///
+/// ```
+/// # use algorithm_study::sorting::{quick_sort_traced, SortEvent};
+/// #
+/// let mut vec: Vec<i32> = (0..100).map(|i| (i * 37) % 100).collect();
+/// let mut partitions = Vec::new();
+///
+/// quick_sort_traced(&mut vec, |e| {
+///     if let SortEvent::PartitionStart { start, end, .. } = e {
+///         partitions.push((start, end));
+///     }
+/// });
+///
+/// assert_eq!(vec, (0..100).collect::<Vec<_>>());
+/// assert_eq!(partitions[0], (0, 100));
+/// // 长度不超过 16 的区间用排序网络，不再分区
+/// assert!(partitions.iter().all(|&(start, end)| end - start > 16));
+/// ```
+///
+pub fn quick_sort_traced<T, F>(vec: &mut [T], on_event: F)
+where
+    T: Ord,
+    F: FnMut(SortEvent),
+{
+    let tracer = Tracer::new(on_event);
+    quick_sort_impl(vec, &mut |a: &T, b: &T| a < b, &tracer);
+}
+
+/// # 回放排序事件
+///
+/// 把 `*_traced` 记录下来的事件依次作用到 `vec` 上。
+/// `vec` 是排序前的输入时，回放结束后与排序的结果相同，可以用来逐帧渲染排序过程。
+///
+/// ```
+/// # use algorithm_study::sorting::{merge_sort_traced, replay_trace};
+/// #
+/// let input = vec![3,5,2,4,1];
+/// let mut events = Vec::new();
+/// merge_sort_traced(&mut input.clone(), |e| events.push(e));
+///
+/// let mut vec = input.clone();
+/// replay_trace(&mut vec, &events);
+///
+/// assert_eq!(vec, vec![1,2,3,4,5]);
+/// ```
+///
+pub fn replay_trace<T: Clone>(vec: &mut [T], events: &[SortEvent]) {
+    // 合并开始时的区间副本，Write 从这里取元素
+    let mut merging: Vec<T> = Vec::new();
+    let mut merge_start = 0;
+
+    for &event in events {
+        match event {
+            SortEvent::Compare(..) | SortEvent::PartitionStart { .. } => {}
+            SortEvent::Swap(i, j) => vec.swap(i, j),
+            SortEvent::Insert { from, to } => vec[to..=from].rotate_right(1),
+            SortEvent::MergeRange { start, end, .. } => {
+                merging = vec[start..end].to_vec();
+                merge_start = start;
+            }
+            SortEvent::Write { to, from } => vec[to] = merging[from - merge_start].clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sorting::{
+        bubble_sort_instrumented, insertion_sort_instrumented, merge_sort_instrumented,
+        quick_sort_instrumented, random, selection_sort_instrumented, SortStats,
+    };
+
+    /// 统计一段事件中的比较、交换和移动次数，与 `*_instrumented` 的结果对应
+    fn count_events(events: &[SortEvent]) -> SortStats {
+        let mut stats = SortStats::default();
+        for event in events {
+            match *event {
+                SortEvent::Compare(..) => stats.comparisons += 1,
+                SortEvent::Swap(..) => stats.swaps += 1,
+                SortEvent::Insert { from, to } => stats.moves += from - to + 1,
+                SortEvent::MergeRange { .. } | SortEvent::PartitionStart { .. } => {}
+                SortEvent::Write { .. } => stats.moves += 1,
+            }
+        }
+        stats
+    }
+
+    type Traced = fn(&mut [u32], &mut dyn FnMut(SortEvent));
+
+    fn traced_sorts() -> [(&'static str, Traced); 5] {
+        [
+            ("bubble", |v, f| bubble_sort_traced(v, f)),
+            ("insertion", |v, f| insertion_sort_traced(v, f)),
+            ("selection", |v, f| selection_sort_traced(v, f)),
+            ("merge", |v, f| merge_sort_traced(v, f)),
+            ("quick", |v, f| quick_sort_traced(v, f)),
+        ]
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut seed = 5;
+
+        for len in [0, 1, 2, 3, 10, 200, 1000] {
+            for range in [2, 50, u64::MAX] {
+                let vec: Vec<u32> = (0..len)
+                    .map(|_| (random(&mut seed) % range) as u32)
+                    .collect();
+
+                for (name, sort) in traced_sorts() {
+                    let mut sorted = vec.clone();
+                    let mut events = Vec::new();
+                    sort(&mut sorted, &mut |e| events.push(e));
+
+                    let mut replayed = vec.clone();
+                    replay_trace(&mut replayed, &events);
+                    assert_eq!(sorted, replayed, "{} len={}", name, len);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_indices_in_range() {
+        let mut seed = 9;
+        let vec: Vec<u32> = (0..500).map(|_| random(&mut seed) as u32).collect();
+
+        for (_, sort) in traced_sorts() {
+            let mut events = Vec::new();
+            sort(&mut vec.clone(), &mut |e| events.push(e));
+
+            for event in events {
+                let ok = match event {
+                    SortEvent::Compare(i, j) | SortEvent::Swap(i, j) => i < 500 && j < 500,
+                    SortEvent::Insert { from, to } => to < from && from < 500,
+                    SortEvent::MergeRange { start, mid, end } => {
+                        start < mid && mid < end && end <= 500
+                    }
+                    SortEvent::Write { to, from } => to < 500 && from < 500,
+                    SortEvent::PartitionStart { start, end, pivot } => {
+                        start <= pivot && pivot < end && end <= 500
+                    }
+                };
+                assert!(ok, "{:?}", event);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fallback_offset() {
+        // 分区层数用尽后对子区间做堆排序，事件的下标仍然是整个数组中的下标
+        let mut seed = 17;
+        let vec: Vec<u32> = (0..500).map(|_| random(&mut seed) as u32).collect();
+
+        let mut sorted = vec.clone();
+        let mut events = Vec::new();
+        crate::sorting::quick_sort_with_idx(
+            &mut sorted,
+            100,
+            400,
+            0,
+            crate::sorting::SmallSort::Network,
+            &mut |a: &u32, b: &u32| a < b,
+            &Tracer::new(|e| events.push(e)),
+        );
+
+        assert!(sorted[100..400].windows(2).all(|w| w[0] <= w[1]));
+        assert!(events.iter().all(|e| match *e {
+            SortEvent::Swap(i, j) => (100..400).contains(&i) && (100..400).contains(&j),
+            _ => true,
+        }));
+
+        let mut replayed = vec.clone();
+        replay_trace(&mut replayed, &events);
+        assert_eq!(sorted, replayed);
+    }
+
+    #[test]
+    fn test_3way_events() {
+        // 三路分区的每次比较都带有下标，重放交换事件得到同样的结果
+        let mut seed = 19;
+        let vec: Vec<u32> = (0..2000).map(|_| (random(&mut seed) % 50) as u32).collect();
+
+        let mut sorted = vec.clone();
+        let mut events = Vec::new();
+        let mut comparisons = 0;
+        crate::sorting::three_way::quick_sort_3way_impl(
+            &mut sorted,
+            &mut |a: &u32, b: &u32| {
+                comparisons += 1;
+                a < b
+            },
+            &Tracer::new(|e| events.push(e)),
+        );
+
+        assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(comparisons, count_events(&events).comparisons);
+
+        let mut replayed = vec.clone();
+        replay_trace(&mut replayed, &events);
+        assert_eq!(sorted, replayed);
+    }
+
+    #[test]
+    fn test_same_as_instrumented() {
+        // 事件与统计来自同一组探针调用，次数应当一致
+        let mut seed = 13;
+        let vec: Vec<u32> = (0..300).map(|_| (random(&mut seed) % 100) as u32).collect();
+
+        let instrumented: [fn(&mut [u32]) -> SortStats; 5] = [
+            bubble_sort_instrumented,
+            insertion_sort_instrumented,
+            selection_sort_instrumented,
+            merge_sort_instrumented,
+            quick_sort_instrumented,
+        ];
+
+        for ((name, sort), stats) in traced_sorts().into_iter().zip(instrumented) {
+            let mut events = Vec::new();
+            sort(&mut vec.clone(), &mut |e| events.push(e));
+
+            let expected = stats(&mut vec.clone());
+            let counted = count_events(&events);
+            assert_eq!(expected.comparisons, counted.comparisons, "{}", name);
+            assert_eq!(expected.swaps, counted.swaps, "{}", name);
+            // 归并时移入辅助空间不产生事件
+            if name != "merge" {
+                assert_eq!(expected.moves, counted.moves, "{}", name);
+            }
+        }
+    }
+}